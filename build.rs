@@ -0,0 +1,45 @@
+//! Generate the pruning tables with the `embedded-tables` feature, see `Tables::embedded`
+
+#[cfg(feature = "embedded-tables")]
+extern crate alloc;
+
+// Only the table construction is needed here, not the rest of the modules
+#[cfg(feature = "embedded-tables")]
+#[allow(dead_code)]
+#[path = "src/cubie.rs"]
+mod cubie;
+#[cfg(feature = "embedded-tables")]
+#[allow(dead_code)]
+#[path = "src/static_tables.rs"]
+mod static_tables;
+
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+	#[cfg(feature = "embedded-tables")]
+	embed_tables();
+}
+
+#[cfg(feature = "embedded-tables")]
+fn embed_tables() {
+	use std::env;
+	use std::fs;
+	use std::path::PathBuf;
+
+	use static_tables::{StaticContext, StaticTables, TableProfile};
+
+	println!("cargo:rerun-if-changed=src/cubie.rs");
+	println!("cargo:rerun-if-changed=src/static_tables.rs");
+
+	let sctx = StaticContext::box_new();
+	let mut stbl = StaticTables::box_zeroed(TableProfile::Standard);
+	stbl.init_move(&sctx, &mut |_| ());
+	stbl.init_prun(&sctx, &mut |_| ());
+	let mut buf = Vec::new();
+	for table in stbl.prun_tables() {
+		for v in table.iter() {
+			buf.extend_from_slice(&v.to_le_bytes());
+		}
+	}
+	let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+	fs::write(out_dir.join("prun_tables.bin"), buf).unwrap();
+}
@@ -0,0 +1,86 @@
+/*
+ * C interface of min2phase, a two-phase solver for the Rubik's cube
+ *
+ * Link with the static or dynamic library built by `cargo build --release -p min2phase-capi`,
+ * i.e. target/release/libmin2phase_capi.a or libmin2phase_capi.so.
+ *
+ * Cubes are represented in facelet, see the documentation of the Rust crate for the
+ * layout, e.g. the solved cube is
+ * "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB".
+ *
+ * Strings are NUL-terminated. Results are written to buffers provided by the caller,
+ * and every function returns MIN2PHASE_OK or a negative error code. Errors about the
+ * cube are the values returned by verify in the Java version of min2phase.
+ *
+ * Keep the functions in sync with capi/src/lib.rs, and the error codes about the cube,
+ * which are the opposite of SolveError::code, in sync with src/error.rs.
+ */
+#ifndef MIN2PHASE_H
+#define MIN2PHASE_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MIN2PHASE_OK 0
+/* there is not exactly one facelet of each colour */
+#define MIN2PHASE_ERR_INVALID_FACELET (-1)
+/* not all 12 edges exist exactly once */
+#define MIN2PHASE_ERR_MISSING_EDGE (-2)
+/* one edge has to be flipped */
+#define MIN2PHASE_ERR_FLIPPED_EDGE (-3)
+/* not all 8 corners exist exactly once */
+#define MIN2PHASE_ERR_MISSING_CORNER (-4)
+/* one corner has to be twisted */
+#define MIN2PHASE_ERR_TWISTED_CORNER (-5)
+/* two corners or two edges have to be exchanged */
+#define MIN2PHASE_ERR_PARITY (-6)
+/* no solution exists within the given length limit */
+#define MIN2PHASE_ERR_NO_SOLUTION (-8)
+/* no solution was found before the probe limit was reached */
+#define MIN2PHASE_ERR_PROBE_LIMIT (-9)
+/* no solution was found before the time limit was reached */
+#define MIN2PHASE_ERR_TIMEOUT (-10)
+/* the search was cancelled */
+#define MIN2PHASE_ERR_CANCELLED (-11)
+/* the output buffer cannot hold the result and its terminating NUL */
+#define MIN2PHASE_ERR_BUFFER_TOO_SMALL (-20)
+/* a pointer is NULL, a string is not UTF-8, the moves are malformed or a number is out of range */
+#define MIN2PHASE_ERR_INVALID_ARGUMENT (-21)
+
+/* size of a buffer large enough for any facelet */
+#define MIN2PHASE_FACELET_SIZE 55
+/* size of a buffer large enough for any solution */
+#define MIN2PHASE_SOLUTION_SIZE 80
+
+/*
+ * Build the tables now rather than on the first solve, which takes a noticeable time.
+ * Return 1 if they were already built, 0 otherwise.
+ */
+int min2phase_init(void);
+
+/*
+ * Solve facelet in at most max_length moves, at most 25, 21 is recommended.
+ * The moves are written to out, separated by spaces, e.g. "R2 U' F".
+ */
+int min2phase_solve(const char *facelet, int max_length, char *out, size_t out_len);
+
+/* Write the facelet of a random cube, uniformly distributed over all solvable states. */
+int min2phase_random_cube(char *out, size_t out_len);
+
+/*
+ * Apply moves, e.g. "R U R' U'", to facelet, or to the solved cube if facelet is NULL.
+ * The facelet of the result is written to out.
+ */
+int min2phase_apply_moves(const char *facelet, const char *moves, char *out, size_t out_len);
+
+/* Check that facelet is a solvable cube, return MIN2PHASE_OK or the reason why it is not. */
+int min2phase_verify(const char *facelet);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MIN2PHASE_H */
@@ -0,0 +1,118 @@
+//! C interface of min2phase, declared in `include/min2phase.h`
+//!
+//! Strings are NUL-terminated. Results are written to buffers provided by the caller, and
+//! every function returns 0 on success or a negative error code. Errors about the cube are
+//! the opposite of [`SolveError::code`], i.e. the values returned by `verify` in the Java
+//! version of min2phase.
+
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+
+use min2phase::{Algorithm, Cube, SolveError, Solver};
+
+pub const MIN2PHASE_OK: c_int = 0;
+/// The output buffer cannot hold the result and its terminating NUL
+pub const MIN2PHASE_ERR_BUFFER_TOO_SMALL: c_int = -20;
+/// A pointer is NULL, a string is not UTF-8, the moves are malformed or a number is out of range
+pub const MIN2PHASE_ERR_INVALID_ARGUMENT: c_int = -21;
+
+fn error_code(err: SolveError) -> c_int {
+	-err.code()
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, c_int> {
+	if s.is_null() {
+		return Err(MIN2PHASE_ERR_INVALID_ARGUMENT);
+	}
+	CStr::from_ptr(s).to_str().map_err(|_| MIN2PHASE_ERR_INVALID_ARGUMENT)
+}
+
+unsafe fn write_str(s: &str, out: *mut c_char, out_len: usize) -> c_int {
+	if out.is_null() {
+		return MIN2PHASE_ERR_INVALID_ARGUMENT;
+	}
+	if s.len() >= out_len {
+		return MIN2PHASE_ERR_BUFFER_TOO_SMALL;
+	}
+	ptr::copy_nonoverlapping(s.as_ptr(), out as *mut u8, s.len());
+	*out.add(s.len()) = 0;
+	MIN2PHASE_OK
+}
+
+/// Build the tables now rather than on the first solve, return 1 if they were already built
+#[no_mangle]
+pub extern "C" fn min2phase_init() -> c_int {
+	min2phase::init() as c_int
+}
+
+/// Solve `facelet` in at most `max_length` moves, writing the moves to `out`
+///
+/// # Safety
+///
+/// `facelet` must be NULL or a NUL-terminated string, `out` must be NULL or point to `out_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn min2phase_solve(facelet: *const c_char, max_length: c_int, out: *mut c_char, out_len: usize) -> c_int {
+	let facelet = match read_str(facelet) {
+		Ok(facelet) => facelet,
+		Err(code) => return code,
+	};
+	let max_length = match u8::try_from(max_length) {
+		Ok(max_length) if max_length <= 25 => max_length,
+		_ => return MIN2PHASE_ERR_INVALID_ARGUMENT,
+	};
+	let ret = Cube::from_facelet(facelet).and_then(|cube| Solver::builder().max_length(max_length).build().solve(&cube));
+	match ret {
+		Ok(solution) => write_str(&solution.to_string(), out, out_len),
+		Err(err) => error_code(err),
+	}
+}
+
+/// Write the facelet of a random cube to `out`
+///
+/// # Safety
+///
+/// `out` must be NULL or point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn min2phase_random_cube(out: *mut c_char, out_len: usize) -> c_int {
+	write_str(&Cube::random().to_facelet(), out, out_len)
+}
+
+/// Apply `moves` to `facelet`, or to the solved cube if `facelet` is NULL, writing the
+/// facelet of the result to `out`
+///
+/// # Safety
+///
+/// `facelet` and `moves` must be NULL or NUL-terminated strings, `out` must be NULL or point
+/// to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn min2phase_apply_moves(facelet: *const c_char, moves: *const c_char, out: *mut c_char, out_len: usize) -> c_int {
+	let cube = if facelet.is_null() {
+		Cube::solved()
+	} else {
+		match read_str(facelet).map(Cube::from_facelet) {
+			Ok(Ok(cube)) => cube,
+			Ok(Err(err)) => return error_code(err),
+			Err(code) => return code,
+		}
+	};
+	let alg: Algorithm = match read_str(moves).map(str::parse) {
+		Ok(Ok(alg)) => alg,
+		_ => return MIN2PHASE_ERR_INVALID_ARGUMENT,
+	};
+	write_str(&cube.apply(&alg).to_facelet(), out, out_len)
+}
+
+/// Check that `facelet` is a solvable cube, return 0 or the reason why it is not
+///
+/// # Safety
+///
+/// `facelet` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn min2phase_verify(facelet: *const c_char) -> c_int {
+	match read_str(facelet).map(Cube::from_facelet) {
+		Ok(Ok(_)) => MIN2PHASE_OK,
+		Ok(Err(err)) => error_code(err),
+		Err(code) => code,
+	}
+}
@@ -0,0 +1,71 @@
+use min2phase_capi::*;
+use min2phase::{Cube, SolveError};
+use std::ffi::{c_char, CStr, CString};
+
+const HEADER: &str = include_str!("../include/min2phase.h");
+const SOURCE: &str = include_str!("../src/lib.rs");
+
+fn out_str(buf: &[c_char]) -> String {
+	unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_string()
+}
+
+#[test]
+fn ffi_functions() {
+	let mut facelet = [0 as c_char; 55];
+	let mut solution = [0 as c_char; 80];
+	unsafe {
+		assert_eq!(min2phase_random_cube(facelet.as_mut_ptr(), facelet.len()), MIN2PHASE_OK);
+		assert_eq!(min2phase_verify(facelet.as_ptr()), MIN2PHASE_OK);
+		assert_eq!(min2phase_solve(facelet.as_ptr(), 21, solution.as_mut_ptr(), solution.len()), MIN2PHASE_OK);
+		let cube = Cube::from_facelet(&out_str(&facelet)).unwrap();
+		assert!(cube.apply(&out_str(&solution).parse().unwrap()).is_solved());
+
+		let moves = CString::new("R U R' U'").unwrap();
+		assert_eq!(min2phase_apply_moves(std::ptr::null(), moves.as_ptr(), facelet.as_mut_ptr(), facelet.len()), MIN2PHASE_OK);
+		assert_eq!(out_str(&facelet), Cube::from_moves("R U R' U'").unwrap().to_facelet());
+		assert_eq!(min2phase_solve(facelet.as_ptr(), 3, solution.as_mut_ptr(), solution.len()), -SolveError::NoSolution.code());
+		assert_eq!(min2phase_solve(facelet.as_ptr(), 21, solution.as_mut_ptr(), 4), MIN2PHASE_ERR_BUFFER_TOO_SMALL);
+		assert_eq!(min2phase_solve(facelet.as_ptr(), 26, solution.as_mut_ptr(), solution.len()), MIN2PHASE_ERR_INVALID_ARGUMENT);
+		assert_eq!(min2phase_random_cube(facelet.as_mut_ptr(), 54), MIN2PHASE_ERR_BUFFER_TOO_SMALL);
+
+		let moves = CString::new("R X").unwrap();
+		assert_eq!(min2phase_apply_moves(std::ptr::null(), moves.as_ptr(), facelet.as_mut_ptr(), facelet.len()), MIN2PHASE_ERR_INVALID_ARGUMENT);
+		assert_eq!(min2phase_verify(std::ptr::null()), MIN2PHASE_ERR_INVALID_ARGUMENT);
+		let short = CString::new("UUU").unwrap();
+		assert_eq!(min2phase_verify(short.as_ptr()), -SolveError::InvalidFacelet.code());
+	}
+}
+
+#[test]
+fn ffi_header() {
+	for line in SOURCE.lines().filter(|line| line.contains("extern \"C\" fn ")) {
+		let name = line.split("fn ").nth(1).unwrap().split('(').next().unwrap();
+		assert!(HEADER.contains(&format!(" {name}(")), "{name} is not declared in min2phase.h");
+	}
+	for err in (1..MIN2PHASE_ERR_BUFFER_TOO_SMALL.abs()).filter_map(SolveError::from_code) {
+		assert!(HEADER.contains(&format!(" ({})", -err.code())), "{err:?} is not defined in min2phase.h");
+	}
+	for line in HEADER.lines().filter(|line| line.starts_with("#define MIN2PHASE_ERR_")) {
+		let fields: Vec<&str> = line.split_whitespace().collect();
+		let code: i32 = fields[2].trim_matches(['(', ')']).parse().unwrap();
+		match fields[1] {
+			"MIN2PHASE_ERR_BUFFER_TOO_SMALL" => assert_eq!(code, MIN2PHASE_ERR_BUFFER_TOO_SMALL),
+			"MIN2PHASE_ERR_INVALID_ARGUMENT" => assert_eq!(code, MIN2PHASE_ERR_INVALID_ARGUMENT),
+			name => {
+				let err = SolveError::from_code(-code).unwrap();
+				assert_eq!(name, format!("MIN2PHASE_ERR_{}", snake_upper(&format!("{err:?}"))));
+			},
+		}
+	}
+}
+
+fn snake_upper(name: &str) -> String {
+	let mut buf = String::new();
+	for (i, c) in name.chars().enumerate() {
+		if c.is_uppercase() && i > 0 {
+			buf.push('_');
+		}
+		buf.push(c.to_ascii_uppercase());
+	}
+	buf
+}
@@ -0,0 +1,283 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Add;
+use core::str::FromStr;
+
+use crate::MOVE2STR;
+
+/// One of the six faces of the cube
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Face {
+	U,
+	R,
+	F,
+	D,
+	L,
+	B,
+}
+
+static FACES: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+/// A face turn, e.g. `R`, `U2` or `F'`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(pub(crate) u8);
+
+impl Move {
+	/// Turn `face` clockwise by `power` quarter turns
+	///
+	/// # Panics
+	///
+	/// Panics if `power` is not 1, 2 or 3.
+	pub fn new(face: Face, power: u8) -> Self {
+		assert!((1..=3).contains(&power), "invalid move power {}", power);
+		Move(face as u8 * 3 + power - 1)
+	}
+
+	pub fn face(&self) -> Face {
+		FACES[(self.0 / 3) as usize]
+	}
+
+	/// Number of clockwise quarter turns, 1, 2 or 3
+	pub fn power(&self) -> u8 {
+		self.0 % 3 + 1
+	}
+
+	pub fn inverse(&self) -> Self {
+		Move(self.0 / 3 * 3 + 2 - self.0 % 3)
+	}
+}
+
+impl fmt::Display for Move {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(MOVE2STR[self.0 as usize].trim_end())
+	}
+}
+
+impl fmt::Debug for Move {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+impl FromStr for Move {
+	type Err = ParseMoveError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let alg: Algorithm = s.parse()?;
+		match alg.moves[..] {
+			[mv] => Ok(mv),
+			[] => Err(ParseMoveError { position: s.len(), found: None }),
+			_ => {
+				let position = s.char_indices().filter(|(_, c)| "URFDLB".contains(*c)).nth(1).unwrap().0;
+				Err(ParseMoveError { position, found: s[position..].chars().next() })
+			}
+		}
+	}
+}
+
+/// Error returned when parsing a [`Move`] or an [`Algorithm`] fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseMoveError {
+	position: usize,
+	found: Option<char>,
+}
+
+impl ParseMoveError {
+	/// Byte offset of the offending character in the input
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// The offending character, or `None` if the input ended unexpectedly
+	pub fn found(&self) -> Option<char> {
+		self.found
+	}
+}
+
+impl fmt::Display for ParseMoveError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.found {
+			Some(c) => write!(f, "unexpected character {:?} at position {}", c, self.position),
+			None => write!(f, "unexpected end of input at position {}", self.position),
+		}
+	}
+}
+
+impl core::error::Error for ParseMoveError {}
+
+/// A sequence of face turns
+///
+/// Parsed from and displayed as the usual notation, e.g. `"R U R' U'"`.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Algorithm {
+	moves: Vec<Move>,
+}
+
+impl Algorithm {
+	pub fn new() -> Self {
+		Algorithm { moves: Vec::new() }
+	}
+
+	pub fn moves(&self) -> &[Move] {
+		&self.moves
+	}
+
+	pub fn iter(&self) -> core::slice::Iter<'_, Move> {
+		self.moves.iter()
+	}
+
+	/// Length in half turn metric
+	pub fn len(&self) -> usize {
+		self.moves.len()
+	}
+
+	/// Length in quarter turn metric
+	pub fn len_qtm(&self) -> usize {
+		self.moves.iter().map(|mv| if mv.power() == 2 { 2 } else { 1 }).sum()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.moves.is_empty()
+	}
+
+	/// Append a move without any simplification
+	pub fn push(&mut self, mv: Move) {
+		self.moves.push(mv);
+	}
+
+	/// Append a move, merging it with the last moves on the same axis,
+	/// e.g. "R L" + "R'" becomes "L", and "U" + "U" becomes "U2"
+	pub fn append(&mut self, mv: Move) {
+		let len = self.moves.len();
+		if len == 0 {
+			self.moves.push(mv);
+			return;
+		}
+
+		let cur_axis = mv.0 / 3;
+		let last_axis = self.moves[len - 1].0 / 3;
+
+		if cur_axis == last_axis {
+			let pow = (mv.0 % 3 + self.moves[len - 1].0 % 3 + 1) % 4;
+			if pow == 3 {
+				self.moves.pop();
+			} else {
+				self.moves[len - 1] = Move(cur_axis * 3 + pow);
+			}
+			return;
+		}
+
+		if len > 1
+			&& cur_axis % 3 == last_axis % 3
+			&& cur_axis == self.moves[len - 2].0 / 3
+		{
+			let pow = (mv.0 % 3 + self.moves[len - 2].0 % 3 + 1) % 4;
+			if pow == 3 {
+				self.moves.remove(len - 2);
+			} else {
+				self.moves[len - 2] = Move(cur_axis * 3 + pow);
+			}
+			return;
+		}
+
+		self.moves.push(mv);
+	}
+
+	/// Concatenate two algorithms, cancelling moves at the junction, see [`Algorithm::append`]
+	pub fn concat(&self, other: &Algorithm) -> Algorithm {
+		let mut alg = self.clone();
+		for &mv in &other.moves {
+			alg.append(mv);
+		}
+		alg
+	}
+
+	/// The algorithm that undoes this one
+	pub fn inverse(&self) -> Algorithm {
+		self.moves.iter().rev().map(|mv| mv.inverse()).collect()
+	}
+}
+
+impl FromStr for Algorithm {
+	type Err = ParseMoveError;
+
+	/// Parse moves matching ```([URFDLB][123'] ?)*```
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut moves = Vec::new();
+		let mut axis = 6;
+		let mut pow = 0;
+		for (position, c) in s.char_indices() {
+			match c {
+				'U' | 'R' | 'F' | 'D' | 'L' | 'B' => {
+					if pow != 0 {
+						moves.push(Move(axis * 3 + pow - 1));
+					}
+					pow = 1;
+					axis = "URFDLB".find(c).unwrap() as u8;
+				},
+				'\'' | '-' | '3' | '2' | '+' | '1' if axis == 6 => {
+					return Err(ParseMoveError { position, found: Some(c) });
+				},
+				'\'' | '-' => pow = (4 - pow) % 4,
+				'3' => pow = pow * 3 % 4,
+				'2' => pow = pow * 2 % 4,
+				'+' | '1' | ' ' | '\t' | '\n' | '\r' => (),
+				_ => {
+					return Err(ParseMoveError { position, found: Some(c) });
+				}
+			}
+		}
+		if pow != 0 {
+			moves.push(Move(axis * 3 + pow - 1));
+		}
+		Ok(Algorithm { moves })
+	}
+}
+
+impl fmt::Display for Algorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, mv) in self.moves.iter().enumerate() {
+			if i != 0 {
+				f.write_str(" ")?;
+			}
+			fmt::Display::fmt(mv, f)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for Algorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Algorithm({})", self)
+	}
+}
+
+impl Add<&Algorithm> for &Algorithm {
+	type Output = Algorithm;
+
+	fn add(self, other: &Algorithm) -> Algorithm {
+		self.concat(other)
+	}
+}
+
+impl From<Vec<Move>> for Algorithm {
+	fn from(moves: Vec<Move>) -> Self {
+		Algorithm { moves }
+	}
+}
+
+impl FromIterator<Move> for Algorithm {
+	fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+		Algorithm { moves: iter.into_iter().collect() }
+	}
+}
+
+impl<'a> IntoIterator for &'a Algorithm {
+	type Item = &'a Move;
+	type IntoIter = core::slice::Iter<'a, Move>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.moves.iter()
+	}
+}
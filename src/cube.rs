@@ -0,0 +1,127 @@
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use rand::RngCore;
+
+use crate::{Algorithm, Cubie, ParseMoveError, SolveError};
+#[cfg(feature = "std")]
+use crate::Solver;
+
+/// A solvable Rubik's cube state
+///
+/// Unlike the facelet strings taken by [`solve`](crate::solve), a `Cube` is always a valid
+/// cube, so it can be moved, composed and inverted without parsing it again.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cube {
+	pub(crate) cc: Cubie,
+}
+
+impl Cube {
+	/// The solved cube
+	pub fn solved() -> Self {
+		Cube { cc: Cubie::new() }
+	}
+
+	/// Parse a cube represented in facelet, see [`solve`](crate::solve) for the layout
+	///
+	/// Return the reason if the facelet is malformed or not a solvable cube
+	pub fn from_facelet(facelet: &str) -> Result<Self, SolveError> {
+		let mut cc = Cubie::new();
+		if cc.from_facelet(facelet) < 0 {
+			return Err(SolveError::InvalidFacelet);
+		}
+		let verify = cc.verify();
+		if verify < 0 {
+			return Err(SolveError::from_code(-verify).unwrap());
+		}
+		Ok(Cube { cc })
+	}
+
+	/// Apply moves to a solved cube
+	///
+	/// * `cube_moves` - should match ```([URFDLB][123'] ?)*```
+	pub fn from_moves(cube_moves: &str) -> Result<Self, ParseMoveError> {
+		Ok(Cube::solved().apply(&cube_moves.parse()?))
+	}
+
+	/// Generate a random cube, uniformly distributed over all solvable states
+	#[cfg(feature = "std")]
+	pub fn random() -> Self {
+		Cube::random_with(&mut rand::thread_rng())
+	}
+
+	/// Same as [`Cube::random`], drawing from `rng`, see [`seeded_rng`](crate::seeded_rng)
+	pub fn random_with<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+		let mut cc = Cubie::new();
+		cc.random_reset(rng);
+		Cube { cc }
+	}
+
+	/// Apply moves to this cube
+	pub fn apply(&self, alg: &Algorithm) -> Self {
+		let mut cc = self.cc;
+		for mv in alg {
+			cc.apply_quarter_turns(mv.0 as usize);
+		}
+		Cube { cc }
+	}
+
+	/// The cube obtained by applying the permutation of `other` after this one
+	///
+	/// `Cube::from_moves(a)?.compose(&Cube::from_moves(b)?)` equals `Cube::from_moves(a + b)?`.
+	pub fn compose(&self, other: &Cube) -> Self {
+		let mut cc = Cubie::new();
+		Cubie::corn_mult(&self.cc, &other.cc, &mut cc);
+		Cubie::edge_mult(&self.cc, &other.cc, &mut cc);
+		Cube { cc }
+	}
+
+	/// The inverse cube, i.e. `self.compose(&self.inverse())` is solved
+	pub fn inverse(&self) -> Self {
+		let mut cc = Cubie::new();
+		Cubie::inv(&self.cc, &mut cc);
+		Cube { cc }
+	}
+
+	pub fn is_solved(&self) -> bool {
+		self.cc == Cubie::new()
+	}
+
+	/// Solve the cube in at most `maxl` moves, see [`solve`](crate::solve)
+	#[cfg(feature = "std")]
+	pub fn solve(&self, maxl: u8) -> Result<Algorithm, SolveError> {
+		Solver::builder().max_length(maxl).build().solve(self)
+	}
+
+	/// Facelet representation of the cube, see [`solve`](crate::solve) for the layout
+	pub fn to_facelet(&self) -> String {
+		self.cc.to_facelet()
+	}
+}
+
+impl Default for Cube {
+	fn default() -> Self {
+		Cube::solved()
+	}
+}
+
+impl FromStr for Cube {
+	type Err = SolveError;
+
+	fn from_str(facelet: &str) -> Result<Self, Self::Err> {
+		Cube::from_facelet(facelet)
+	}
+}
+
+impl fmt::Display for Cube {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.to_facelet())
+	}
+}
+
+impl fmt::Debug for Cube {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Cube({})", self.to_facelet())
+	}
+}
@@ -0,0 +1,278 @@
+use crate::static_tables::MOVE_BASE;
+#[cfg(feature = "std")]
+use crate::static_tables::StaticContext;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Cubie {
+	pub(crate) ca: [u8; 8],
+	pub(crate) ea: [u8; 12],
+}
+
+impl Cubie {
+	pub(crate) fn new() -> Self {
+		let mut cc = Cubie {ca: [0; 8], ea: [0; 12]};
+		cc.reset();
+		cc
+	}
+
+	pub(crate) fn reset(&mut self) {
+		for i in 0..8 {
+			self.ca[i] = i as u8;
+		}
+		for i in 0..12 {
+			self.ea[i] = i as u8 * 2;
+		}
+	}
+
+	pub(crate) fn cmp(&self, other: &Cubie) -> i8 {
+		for i in 0..8 {
+			if self.ca[i] != other.ca[i] {
+				return (self.ca[i] as i8) - (other.ca[i] as i8);
+			}
+		}
+		for i in 0..12 {
+			if self.ea[i] != other.ea[i] {
+				return (self.ea[i] as i8) - (other.ea[i] as i8);
+			}
+		}
+		0
+	}
+
+	pub(crate) fn corn_mult(a: &Cubie, b: &Cubie, prod: &mut Cubie) {
+		for cn in 0..8 {
+			let ori_a = a.ca[(b.ca[cn] & 0x7) as usize] >> 3;
+			let ori_b = b.ca[cn] >> 3;
+			let mut ori = ori_a + if ori_a < 3 { ori_b } else { 6 - ori_b };
+			ori = ori % 3 + if (ori_a < 3) == (ori_b < 3) { 0 } else { 3 };
+			prod.ca[cn] = (a.ca[(b.ca[cn] & 0x7) as usize] & 0x7) | (ori << 3);
+		}
+	}
+
+	pub(crate) fn edge_mult(a: &Cubie, b: &Cubie, prod: &mut Cubie) {
+		for ed in 0..12 {
+			prod.ea[ed] = a.ea[(b.ea[ed] >> 1) as usize] ^ (b.ea[ed] & 1);
+		}
+	}
+
+	pub(crate) fn inv(src: &Cubie, inv: &mut Cubie) {
+		for ed in 0..12 {
+			inv.ea[(src.ea[ed] >> 1) as usize] = (ed as u8 * 2) | (src.ea[ed] & 0x1);
+		}
+		for cn in 0..8 {
+			inv.ca[(src.ca[cn] & 0x7) as usize] = cn as u8 | (((0x20 >> (src.ca[cn] >> 3)) & 0x18) as u8);
+		}
+	}
+
+	#[cfg(feature = "std")]
+	pub(crate) fn apply_move(&mut self, sctx: &StaticContext, mv: usize) {
+		let mut cd = Cubie::new();
+		Cubie::corn_mult(self, &sctx.movecube[mv], &mut cd);
+		Cubie::edge_mult(self, &sctx.movecube[mv], &mut cd);
+		*self = cd;
+	}
+
+	/// Same as [`Cubie::apply_move`] without the tables, as `mv % 3 + 1` quarter turns
+	pub(crate) fn apply_quarter_turns(&mut self, mv: usize) {
+		let mut cd = Cubie::new();
+		for _ in 0..mv % 3 + 1 {
+			Cubie::corn_mult(self, &MOVE_BASE[mv / 3], &mut cd);
+			Cubie::edge_mult(self, &MOVE_BASE[mv / 3], &mut cd);
+			*self = cd;
+		}
+	}
+}
+
+pub(crate) fn get_nparity(mut idx: i32, n: i32) -> i32 {
+	let mut p = 0;
+	let mut i = n - 2;
+	while i >= 0 {
+		p ^= idx % (n - i);
+		idx /= n - i;
+		i -= 1;
+	}
+	p & 1
+}
+
+pub(crate) fn get_nperm(arr: &[u8], n: i32) -> i32 {
+	let mut idx = 0;
+	let mut val = 0x76543210;
+	for i in 0..(n - 1) {
+		let v = arr[i as usize] << 2;
+		idx = (n - i) * idx + ((val >> v) & 0xf);
+		val -= 0x11111110 << v;
+	}
+	idx
+}
+
+pub(crate) fn set_nperm(arr: &mut [u8], mut idx: u16, n: u16) {
+	let mut extract = 0;
+	let mut val = 0x76543210;
+	for i in 2..=n {
+		extract = (extract << 4) | (idx % i) as u32;
+		idx /= i;
+	}
+	for i in 0..(n - 1) {
+		let v = (extract & 0xf) << 2;
+		extract >>= 4;
+		arr[i as usize] = ((val >> v) & 0xf) as u8;
+		let m = (1 << v) - 1;
+		val = (val & m) | ((val >> 4) & !m);
+	}
+	arr[(n - 1) as usize] = (val & 0xf) as u8;
+}
+
+pub(crate) fn get_comb(arr: &[u8], n: i32, mask: i32) -> i32 {
+	let mut idx_c = 0;
+	let mut r = 4;
+	let mut cnk = if n == 12 { 330 } else { 35 };
+	for i in (0..n).rev() {
+		if (arr[i as usize] & 0xc) == mask as u8 {
+			idx_c += cnk;
+			cnk = cnk * r / core::cmp::max(1, i - r + 1);
+			r -= 1;
+		}
+		cnk = cnk * (i - r) / core::cmp::max(1, i);
+	}
+	idx_c
+}
+
+pub(crate) fn set_comb(arr: &mut [u8], mut idx_c: i32, n: i32, mask: i32) {
+	let mut r = 4;
+	let mut fill = n - 1;
+	let mut cnk = if n == 12 { 330 } else { 35 };
+	for i in (0..n).rev() {
+		if idx_c >= cnk {
+			idx_c -= cnk;
+			cnk = cnk * r / core::cmp::max(1, i - r + 1);
+			r -= 1;
+			arr[i as usize] = (r | mask) as u8;
+		} else {
+			if (fill & 0xc) == mask {
+				fill -= 4;
+			}
+			arr[i as usize] = fill as u8;
+			fill -= 1;
+		}
+		cnk = cnk * (i - r) / core::cmp::max(1, i);
+	}
+}
+
+impl Cubie {
+	pub(crate) fn get_flip(&self) -> i32 {
+		let mut idx = 0;
+		for i in 0..11 {
+			idx = (idx << 1) | (self.ea[i] & 1) as i32;
+		}
+		idx
+	}
+
+	pub(crate) fn set_flip(&mut self, mut idx: u16) {
+		let mut parity = 0;
+		for i in (0..11).rev() {
+			let val = idx & 1;
+			idx >>= 1;
+			parity ^= val;
+			self.ea[i] = (self.ea[i] & !1) | (val as u8);
+		}
+		self.ea[11] = (self.ea[11] & !1) | (parity as u8);
+	}
+
+	pub(crate) fn get_twst(&self) -> i32 {
+		let mut idx = 0;
+		for i in 0..7 {
+			idx += (idx << 1) + (self.ca[i] >> 3) as i32;
+		}
+		idx
+	}
+
+	pub(crate) fn set_twst(&mut self, mut idx: u16) {
+		let mut twst = 15;
+		for i in (0..7).rev() {
+			let val = idx % 3;
+			idx /= 3;
+			twst -= val;
+			self.ca[i] = (self.ca[i] & 0x7) | (val << 3) as u8;
+		}
+		self.ca[7] = (self.ca[7] & 0x7) | ((twst % 3) << 3) as u8;
+	}
+
+	pub(crate) fn get_slice(&self) -> u16 {
+		let mut arr = [0u8; 12];
+		for (a, &e) in arr.iter_mut().zip(&self.ea) {
+			*a = e >> 1;
+		}
+		494 - get_comb(&arr, 12, 8) as u16
+	}
+
+	pub(crate) fn set_slice(&mut self, idx: i32) {
+		let mut arr = [0u8; 12];
+		set_comb(&mut arr, 494 - idx, 12, 8);
+		for (e, &a) in self.ea.iter_mut().zip(&arr) {
+			*e = (*e & 1) | (a << 1);
+		}
+	}
+
+	pub(crate) fn get_cperm(&self) -> i32 {
+		let mut arr = [0u8; 8];
+		for (a, &c) in arr.iter_mut().zip(&self.ca) {
+			*a = c & 0x7;
+		}
+		get_nperm(&arr, 8)
+	}
+
+	pub(crate) fn set_cperm(&mut self, idx: u16) {
+		let mut arr = [0u8; 8];
+		set_nperm(&mut arr, idx, 8);
+		for (c, &a) in self.ca.iter_mut().zip(&arr) {
+			*c = (*c & !0x7) | a;
+		}
+	}
+
+	pub(crate) fn get_eperm(&self) -> i32 {
+		let mut arr = [0u8; 8];
+		for (a, &e) in arr.iter_mut().zip(&self.ea) {
+			*a = e >> 1;
+		}
+		get_nperm(&arr, 8)
+	}
+
+	pub(crate) fn set_eperm(&mut self, idx: u16) {
+		let mut arr = [0u8; 8];
+		set_nperm(&mut arr, idx, 8);
+		for (e, &a) in self.ea.iter_mut().zip(&arr) {
+			*e = (*e & 1) | (a << 1);
+		}
+	}
+
+	pub(crate) fn get_mperm(&self) -> i32 {
+		let mut arr = [0u8; 4];
+		for (a, &e) in arr.iter_mut().zip(&self.ea[8..]) {
+			*a = (e >> 1) & 0x3;
+		}
+		get_nperm(&arr, 4)
+	}
+
+	pub(crate) fn set_mperm(&mut self, idx: u16) {
+		let mut arr = [0u8; 4];
+		set_nperm(&mut arr, idx, 4);
+		for (e, &a) in self.ea[8..].iter_mut().zip(&arr) {
+			*e = (*e & 1) | ((a + 8) << 1);
+		}
+	}
+
+	pub(crate) fn get_ccomb(&self) -> i32 {
+		let mut arr = [0u8; 8];
+		for (a, &c) in arr.iter_mut().zip(&self.ca) {
+			*a = c & 0x7;
+		}
+		get_comb(&arr, 8, 0)
+	}
+
+	pub(crate) fn set_ccomb(&mut self, idx: i32) {
+		let mut arr = [0u8; 8];
+		set_comb(&mut arr, idx, 8, 0);
+		for (c, &a) in self.ca.iter_mut().zip(&arr) {
+			*c = (*c & !0x7) | a;
+		}
+	}
+}
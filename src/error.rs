@@ -0,0 +1,84 @@
+use core::fmt;
+
+/// Reasons why a cube cannot be solved
+///
+/// Each variant has an error code, see [`SolveError::code`], that is compatible with the
+/// "Error N" strings returned by [`solve`](crate::solve) and the Java version of min2phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SolveError {
+	/// The facelet is too short, or there is not exactly one facelet of each colour
+	InvalidFacelet,
+	/// Not all 12 edges exist exactly once
+	MissingEdge,
+	/// One edge has to be flipped
+	FlippedEdge,
+	/// Not all 8 corners exist exactly once
+	MissingCorner,
+	/// One corner has to be twisted
+	TwistedCorner,
+	/// Two corners or two edges have to be exchanged
+	Parity,
+	/// No solution exists within the given length limit
+	NoSolution,
+	/// No solution was found before the probe limit was reached
+	ProbeLimit,
+	/// No solution was found before the time limit was reached
+	Timeout,
+	/// The search was cancelled through a [`CancelToken`](crate::CancelToken)
+	Cancelled,
+}
+
+impl SolveError {
+	/// Error code of the legacy string API, i.e. `solve` returns "Error {code}"
+	pub fn code(&self) -> i32 {
+		match self {
+			SolveError::InvalidFacelet => 1,
+			SolveError::MissingEdge => 2,
+			SolveError::FlippedEdge => 3,
+			SolveError::MissingCorner => 4,
+			SolveError::TwistedCorner => 5,
+			SolveError::Parity => 6,
+			SolveError::NoSolution => 8,
+			SolveError::ProbeLimit => 9,
+			SolveError::Timeout => 10,
+			SolveError::Cancelled => 11,
+		}
+	}
+
+	/// Inverse of [`SolveError::code`]
+	pub fn from_code(code: i32) -> Option<Self> {
+		match code {
+			1 => Some(SolveError::InvalidFacelet),
+			2 => Some(SolveError::MissingEdge),
+			3 => Some(SolveError::FlippedEdge),
+			4 => Some(SolveError::MissingCorner),
+			5 => Some(SolveError::TwistedCorner),
+			6 => Some(SolveError::Parity),
+			8 => Some(SolveError::NoSolution),
+			9 => Some(SolveError::ProbeLimit),
+			10 => Some(SolveError::Timeout),
+			11 => Some(SolveError::Cancelled),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for SolveError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			SolveError::InvalidFacelet => "invalid facelet, there is not exactly one facelet of each colour",
+			SolveError::MissingEdge => "not all 12 edges exist exactly once",
+			SolveError::FlippedEdge => "flip error, one edge has to be flipped",
+			SolveError::MissingCorner => "not all 8 corners exist exactly once",
+			SolveError::TwistedCorner => "twist error, one corner has to be twisted",
+			SolveError::Parity => "parity error, two corners or two edges have to be exchanged",
+			SolveError::NoSolution => "no solution exists within the given length limit",
+			SolveError::ProbeLimit => "no solution found within the given probe limit",
+			SolveError::Timeout => "no solution found within the given time limit",
+			SolveError::Cancelled => "the search was cancelled",
+		})
+	}
+}
+
+impl core::error::Error for SolveError {}
@@ -0,0 +1,21 @@
+//! JSON output shared by the serve protocol and the command-line binary, which uses it through
+//! the hidden `min2phase::json_str`
+
+use alloc::format;
+use alloc::string::String;
+
+/// Quote a string as a JSON string literal
+pub fn json_str(s: &str) -> String {
+	let mut buf = String::with_capacity(s.len() + 2);
+	buf.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => buf.push_str("\\\""),
+			'\\' => buf.push_str("\\\\"),
+			c if (c as u32) < 0x20 => buf.push_str(&format!("\\u{:04x}", c as u32)),
+			c => buf.push(c),
+		}
+	}
+	buf.push('"');
+	buf
+}
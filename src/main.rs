@@ -0,0 +1,298 @@
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use min2phase::{json_str, Algorithm, Cube, SolveError, Solver, TableProfile, Tables};
+use rand::RngCore;
+
+const USAGE: &str = "\
+Usage: min2phase <command> [options] [args]
+
+Commands:
+  solve [FACELET...]   solve the cubes given as arguments, or one per line from stdin
+  scramble             print random-state scrambles, or random-move scrambles with --moves
+  apply MOVES          apply moves to the solved cube, or to the cube given with --facelet
+  verify [FACELET...]  check that the cubes are solvable, read from stdin if none is given
+  bench                solve random cubes and report the timing
+  serve                solve the requests read line by line from stdin, see the
+                       documentation of min2phase::serve for the protocol
+
+Options:
+  --json               print one JSON object per line instead of plain text
+  --max-length N       maximum solution length, 21 by default
+  --time-limit MS      stop searching after MS milliseconds
+  --min-probes N       keep searching for shorter solutions for N probes
+  --threads N          search each cube on up to N threads
+  --optimal            find shortest solutions, can be very slow
+  --profile NAME       tables to solve with: standard (default), low-memory or twist-flip
+  --inverse            print the moves generating the cube instead of solving it
+  --count N            number of scrambles or benchmarked cubes, 1 and 100 by default
+  --moves N            number of moves of random-move scrambles
+  --seed N             generate reproducible scrambles or benchmarked cubes from the seed N
+  --facelet FACELET    cube the moves are applied to
+  -h, --help           print this help
+
+Failures are reported as \"Error N\", see the library documentation for the codes.
+The exit status is 1 if any cube failed, 2 on invalid usage.";
+
+#[derive(Default)]
+struct Options {
+	json: bool,
+	max_length: Option<u8>,
+	time_limit: Option<u64>,
+	min_probes: Option<u64>,
+	threads: Option<u8>,
+	optimal: bool,
+	profile: Option<TableProfile>,
+	inverse: bool,
+	count: Option<usize>,
+	moves: Option<u16>,
+	seed: Option<u64>,
+	facelet: Option<String>,
+	help: bool,
+	args: Vec<String>,
+}
+
+impl Options {
+	fn parse<I: Iterator<Item = String>>(mut iter: I) -> Result<Self, String> {
+		let mut opts = Options::default();
+		while let Some(arg) = iter.next() {
+			match arg.as_str() {
+				"--json" => opts.json = true,
+				"--optimal" => opts.optimal = true,
+				"--inverse" => opts.inverse = true,
+				"-h" | "--help" => opts.help = true,
+				"--max-length" => opts.max_length = Some(parse_value(&arg, iter.next())?),
+				"--time-limit" => opts.time_limit = Some(parse_value(&arg, iter.next())?),
+				"--min-probes" => opts.min_probes = Some(parse_value(&arg, iter.next())?),
+				"--threads" => opts.threads = Some(parse_value(&arg, iter.next())?),
+				"--profile" => opts.profile = Some(parse_profile(iter.next())?),
+				"--count" => opts.count = Some(parse_value(&arg, iter.next())?),
+				"--moves" => opts.moves = Some(parse_value(&arg, iter.next())?),
+				"--seed" => opts.seed = Some(parse_value(&arg, iter.next())?),
+				"--facelet" => opts.facelet = Some(iter.next().ok_or("missing value of --facelet")?),
+				_ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("unknown option {arg}")),
+				_ => opts.args.push(arg),
+			}
+		}
+		Ok(opts)
+	}
+
+	fn solver(&self) -> Solver {
+		let mut builder = Solver::builder()
+			.max_length(self.max_length.unwrap_or(21))
+			.min_probes(self.min_probes.unwrap_or(0))
+			.threads(self.threads.unwrap_or(1))
+			.inverse_solution(self.inverse);
+		if let Some(limit) = self.time_limit {
+			builder = builder.time_limit(Duration::from_millis(limit));
+		}
+		if let Some(profile) = self.profile {
+			builder = builder.tables(Arc::new(Tables::build_with_profile(profile, |_| ())));
+		}
+		builder.build()
+	}
+
+	/// Seeded random numbers with --seed, thread-local ones otherwise
+	fn rng(&self) -> Box<dyn RngCore> {
+		match self.seed {
+			Some(seed) => Box::new(min2phase::seeded_rng(seed)),
+			None => Box::new(rand::thread_rng()),
+		}
+	}
+}
+
+/// Names of the table profiles in the options
+const PROFILES: [(&str, TableProfile); 3] = [
+	("standard", TableProfile::Standard),
+	("low-memory", TableProfile::LowMemory),
+	("twist-flip", TableProfile::TwistFlip),
+];
+
+fn parse_profile(value: Option<String>) -> Result<TableProfile, String> {
+	let value = value.ok_or("missing value of --profile")?;
+	PROFILES.iter().find(|(name, _)| *name == value).map(|&(_, profile)| profile)
+		.ok_or(format!("invalid value of --profile: {value}"))
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+	let value = value.ok_or(format!("missing value of {name}"))?;
+	value.parse().map_err(|_| format!("invalid value of {name}: {value}"))
+}
+
+/// The arguments, or the non-empty lines of stdin if there is none
+fn inputs(args: &[String]) -> Box<dyn Iterator<Item = String> + '_> {
+	if args.is_empty() {
+		Box::new(io::stdin().lock().lines().map_while(Result::ok).map(|line| line.trim().to_string()).filter(|line| !line.is_empty()))
+	} else {
+		Box::new(args.iter().cloned())
+	}
+}
+
+fn json_error(err: SolveError) -> String {
+	format!("\"error\":{},\"code\":{}", json_str(&err.to_string()), err.code())
+}
+
+fn solve(opts: &Options, out: &mut impl Write) -> io::Result<bool> {
+	let solver = opts.solver();
+	let mut ok = true;
+	for facelet in inputs(&opts.args) {
+		let ret = Cube::from_facelet(&facelet).and_then(|cube| {
+			if opts.optimal {
+				solver.solve_optimal(&cube).map(|solution| if opts.inverse { solution.inverse() } else { solution })
+			} else {
+				solver.solve(&cube)
+			}
+		});
+		ok &= ret.is_ok();
+		match (opts.json, ret) {
+			(false, Ok(solution)) => writeln!(out, "{solution}")?,
+			(false, Err(err)) => writeln!(out, "Error {}", err.code())?,
+			(true, Ok(solution)) => writeln!(out, "{{\"facelet\":{},\"solution\":{},\"length\":{}}}",
+				json_str(&facelet), json_str(&solution.to_string()), solution.len())?,
+			(true, Err(err)) => writeln!(out, "{{\"facelet\":{},{}}}", json_str(&facelet), json_error(err))?,
+		}
+	}
+	Ok(ok)
+}
+
+fn scramble(opts: &Options, out: &mut impl Write) -> io::Result<bool> {
+	let solver = Solver::builder().max_length(opts.max_length.unwrap_or(21)).inverse_solution(true).build();
+	let mut rng = opts.rng();
+	for _ in 0..opts.count.unwrap_or(1) {
+		let scramble: Algorithm = match opts.moves {
+			Some(n_moves) => min2phase::random_moves_with(&mut rng, n_moves).parse().unwrap(),
+			None => match solver.solve(&Cube::random_with(&mut rng)) {
+				Ok(scramble) => scramble,
+				Err(err) => {
+					writeln!(out, "Error {}", err.code())?;
+					return Ok(false);
+				},
+			},
+		};
+		if opts.json {
+			let facelet = Cube::solved().apply(&scramble).to_facelet();
+			writeln!(out, "{{\"scramble\":{},\"facelet\":{}}}", json_str(&scramble.to_string()), json_str(&facelet))?;
+		} else {
+			writeln!(out, "{scramble}")?;
+		}
+	}
+	Ok(true)
+}
+
+fn apply(opts: &Options, out: &mut impl Write) -> Result<bool, String> {
+	let cube = match &opts.facelet {
+		Some(facelet) => Cube::from_facelet(facelet).map_err(|err| format!("invalid facelet: {err}"))?,
+		None => Cube::solved(),
+	};
+	let alg: Algorithm = opts.args.join(" ").parse().map_err(|err| format!("invalid moves: {err}"))?;
+	let facelet = cube.apply(&alg).to_facelet();
+	let ret = if opts.json {
+		writeln!(out, "{{\"facelet\":{}}}", json_str(&facelet))
+	} else {
+		writeln!(out, "{facelet}")
+	};
+	ret.map(|_| true).map_err(|err| err.to_string())
+}
+
+fn verify(opts: &Options, out: &mut impl Write) -> io::Result<bool> {
+	let mut ok = true;
+	for facelet in inputs(&opts.args) {
+		let ret = Cube::from_facelet(&facelet);
+		ok &= ret.is_ok();
+		match (opts.json, ret) {
+			(false, Ok(_)) => writeln!(out, "OK")?,
+			(false, Err(err)) => writeln!(out, "Error {}: {}", err.code(), err)?,
+			(true, Ok(_)) => writeln!(out, "{{\"facelet\":{},\"valid\":true}}", json_str(&facelet))?,
+			(true, Err(err)) => writeln!(out, "{{\"facelet\":{},\"valid\":false,{}}}", json_str(&facelet), json_error(err))?,
+		}
+	}
+	Ok(ok)
+}
+
+fn bench(opts: &Options, out: &mut impl Write) -> io::Result<bool> {
+	let count = opts.count.unwrap_or(100);
+	let now = Instant::now();
+	if opts.profile.is_none() {
+		min2phase::init();
+	}
+	let solver = opts.solver();
+	let init = now.elapsed();
+
+	let mut rng = opts.rng();
+	let cubes: Vec<Cube> = (0..count).map(|_| Cube::random_with(&mut rng)).collect();
+	let mut total_length = 0;
+	let mut failed = 0;
+	let (mut probes, mut nodes1, mut nodes2) = (0, 0, 0);
+	let now = Instant::now();
+	for cube in &cubes {
+		let (ret, stats) = solver.solve_with_stats(cube);
+		match ret {
+			Ok(solution) => total_length += solution.len(),
+			Err(_) => failed += 1,
+		}
+		probes += stats.probes;
+		nodes1 += stats.phase1_nodes;
+		nodes2 += stats.phase2_nodes;
+	}
+	let elapsed = now.elapsed();
+	let solved = count - failed;
+	let avg_length = if solved > 0 { total_length as f64 / solved as f64 } else { 0.0 };
+	let avg_ms = if count > 0 { elapsed.as_secs_f64() * 1e3 / count as f64 } else { 0.0 };
+	let profile = opts.profile.unwrap_or_default();
+	let profile = PROFILES.iter().find(|&&(_, p)| p == profile).unwrap().0;
+	let avg = |total: u64| if count > 0 { total as f64 / count as f64 } else { 0.0 };
+	if opts.json {
+		writeln!(out, "{{\"profile\":{},\"cubes\":{},\"failed\":{},\"init_ms\":{:.3},\"total_ms\":{:.3},\"avg_ms\":{:.3},\"avg_length\":{:.3},\"avg_probes\":{:.1},\"avg_phase1_nodes\":{:.1},\"avg_phase2_nodes\":{:.1}}}",
+			json_str(profile), count, failed, init.as_secs_f64() * 1e3, elapsed.as_secs_f64() * 1e3, avg_ms, avg_length, avg(probes), avg(nodes1), avg(nodes2))?;
+	} else {
+		writeln!(out, "init:    {:.3} ms, {profile} tables", init.as_secs_f64() * 1e3)?;
+		writeln!(out, "solved:  {solved}/{count} cubes in {:.3} ms", elapsed.as_secs_f64() * 1e3)?;
+		writeln!(out, "average: {avg_ms:.3} ms, {avg_length:.2} moves")?;
+		writeln!(out, "search:  {:.1} probes, {:.1} phase 1 nodes, {:.1} phase 2 nodes", avg(probes), avg(nodes1), avg(nodes2))?;
+	}
+	Ok(failed == 0)
+}
+
+fn main() -> ExitCode {
+	let mut args = std::env::args().skip(1);
+	let Some(command) = args.next() else {
+		eprintln!("{USAGE}");
+		return ExitCode::from(2);
+	};
+	if command == "-h" || command == "--help" || command == "help" {
+		println!("{USAGE}");
+		return ExitCode::SUCCESS;
+	}
+	let opts = match Options::parse(args) {
+		Ok(opts) => opts,
+		Err(msg) => {
+			eprintln!("min2phase: {msg}");
+			return ExitCode::from(2);
+		},
+	};
+	if opts.help {
+		println!("{USAGE}");
+		return ExitCode::SUCCESS;
+	}
+
+	let mut out = io::stdout().lock();
+	let ret = match command.as_str() {
+		"solve" => solve(&opts, &mut out).map_err(|err| err.to_string()),
+		"scramble" => scramble(&opts, &mut out).map_err(|err| err.to_string()),
+		"apply" => apply(&opts, &mut out),
+		"verify" => verify(&opts, &mut out).map_err(|err| err.to_string()),
+		"bench" => bench(&opts, &mut out).map_err(|err| err.to_string()),
+		"serve" => min2phase::serve(&opts.solver(), io::stdin().lock(), &mut out).map(|_| true).map_err(|err| err.to_string()),
+		_ => Err(format!("unknown command {command}, see min2phase --help")),
+	};
+	match ret {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::from(1),
+		Err(msg) => {
+			eprintln!("min2phase: {msg}");
+			ExitCode::from(2)
+		},
+	}
+}
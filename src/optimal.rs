@@ -0,0 +1,282 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+	esym2csym, flip_raw_tables, get_pruning, init_raw_sym_prun, Algorithm, CancelToken, Cubie, Move,
+	SolveError, StaticContext, StaticTables, N_FLIP, N_MOVES_P1, N_PERM, N_PERM_SYM, N_SLICE, N_TWST,
+	URF_MOVE,
+};
+
+/// Raw flip x slice coordinate, slice * N_FLIP + flip
+const N_FLIPSLICE: usize = N_FLIP * N_SLICE;
+/// Classes of flip x slice under the 16 symmetries, which keep the UD slice
+const N_FLIPSLICE_SYM: usize = 64430;
+
+/// Tables only needed by the optimal solver, built on the first optimal solve
+///
+/// The search is pruned by
+/// - twist x flip x slice, reduced by the 16 symmetries of flip x slice, looked up along the
+///   three axes, about 70MB
+/// - corner permutation x twist, reduced by the 16 symmetries of corner permutation
+pub(crate) struct OptimalTables {
+	urf_movei: [[u8; N_MOVES_P1]; 3],
+	twst_move: Vec<u16>,
+	twst_conj: Vec<u16>,
+	flip_move: Vec<u16>,
+	cperm_move: Vec<u16>,
+	flipslice_raw2sym: Vec<u32>,
+	flipslice_twst_prun: Vec<u32>,
+	cperm_twst_prun: Vec<u32>,
+}
+
+impl OptimalTables {
+	pub(crate) fn box_new(sctx: &StaticContext, stbl: &StaticTables) -> Box<Self> {
+		let (flip_move, _) = flip_raw_tables(sctx);
+		let mut otbl = Box::new(OptimalTables {
+			urf_movei: [[0; N_MOVES_P1]; 3],
+			twst_move: vec![0; N_TWST * N_MOVES_P1],
+			twst_conj: vec![0; N_TWST * 16],
+			flip_move,
+			cperm_move: vec![0; N_PERM * N_MOVES_P1],
+			flipslice_raw2sym: vec![u32::MAX; N_FLIPSLICE],
+			flipslice_twst_prun: vec![0; N_TWST * N_FLIPSLICE_SYM / 8 + 1],
+			cperm_twst_prun: vec![0; N_TWST * N_PERM_SYM / 8 + 1],
+		});
+		otbl.init(sctx, stbl);
+		otbl
+	}
+
+	#[allow(clippy::needless_range_loop)]
+	fn init(&mut self, sctx: &StaticContext, stbl: &StaticTables) {
+		for urf in 0..3 {
+			for m in 0..N_MOVES_P1 {
+				self.urf_movei[urf][URF_MOVE[urf][m] as usize] = m as u8;
+			}
+		}
+
+		let mut c = Cubie::new();
+		let mut d = Cubie::new();
+		let mut e = Cubie::new();
+		for i in 0..N_TWST {
+			c.set_twst(i as u16);
+			for j in 0..N_MOVES_P1 {
+				Cubie::corn_mult(&c, &sctx.movecube[j], &mut d);
+				self.twst_move[i * N_MOVES_P1 + j] = d.get_twst() as u16;
+			}
+			for j in 0..16 {
+				Cubie::corn_mult(&sctx.symcube[j], &c, &mut e);
+				Cubie::corn_mult(&e, &sctx.symcube[sctx.symmuli[0][j] as usize], &mut d);
+				self.twst_conj[i * 16 + j] = d.get_twst() as u16;
+			}
+		}
+
+		c.reset();
+		for i in 0..N_PERM {
+			c.set_cperm(i as u16);
+			for j in 0..N_MOVES_P1 {
+				Cubie::corn_mult(&c, &sctx.movecube[j], &mut d);
+				self.cperm_move[i * N_MOVES_P1 + j] = d.get_cperm() as u16;
+			}
+		}
+		let mut csym_move = vec![0u16; N_PERM_SYM * N_MOVES_P1];
+		for i in 0..N_PERM_SYM {
+			for j in 0..N_MOVES_P1 {
+				let cperm = self.cperm_move[stbl.eperm_sym2raw[i] as usize * N_MOVES_P1 + j];
+				csym_move[i * N_MOVES_P1 + j] = esym2csym(stbl.eperm_raw2sym(sctx, cperm));
+			}
+		}
+
+		let (flipslice_move, flipslice_selfsym) = self.init_flipslice(sctx, stbl);
+		init_raw_sym_prun(&mut self.flipslice_twst_prun, &self.twst_move, &self.twst_conj, &flipslice_move, &flipslice_selfsym, N_TWST, N_FLIPSLICE_SYM, 0xc804, "flipslice_twst_prun", &mut |_| ());
+		init_raw_sym_prun(&mut self.cperm_twst_prun, &self.twst_move, &self.twst_conj, &csym_move, &stbl.eperm_selfsym, N_TWST, N_PERM_SYM, 0xb814, "cperm_twst_prun", &mut |_| ());
+	}
+
+	/// Classify flip x slice by symmetry, as `init_sym2raw` does for the single coordinates,
+	/// return the moves of the classes, as class << 4 | symmetry, and their self-symmetries
+	fn init_flipslice(&mut self, sctx: &StaticContext, stbl: &StaticTables) -> (Vec<u32>, Vec<u16>) {
+		let mut sym2raw = Vec::with_capacity(N_FLIPSLICE_SYM);
+		let mut selfsym = Vec::with_capacity(N_FLIPSLICE_SYM);
+		let mut c = Cubie::new();
+		let mut d = Cubie::new();
+		let mut e = Cubie::new();
+		for i in 0..N_FLIPSLICE {
+			if self.flipslice_raw2sym[i] != u32::MAX {
+				continue;
+			}
+			c.set_slice((i / N_FLIP) as i32);
+			c.set_flip((i % N_FLIP) as u16);
+			let mut ssmask = 0;
+			for s in 0..16 {
+				Cubie::edge_mult(&sctx.symcube[sctx.symmuli[0][s] as usize], &c, &mut e);
+				Cubie::edge_mult(&e, &sctx.symcube[s], &mut d);
+				let idx = d.get_slice() as usize * N_FLIP + d.get_flip() as usize;
+				if idx == i {
+					ssmask |= 1 << s;
+				}
+				self.flipslice_raw2sym[idx] = (sym2raw.len() << 4 | s) as u32;
+			}
+			sym2raw.push(i);
+			selfsym.push(ssmask);
+		}
+		debug_assert_eq!(sym2raw.len(), N_FLIPSLICE_SYM);
+
+		let mut sym_move = vec![0; N_FLIPSLICE_SYM * N_MOVES_P1];
+		for (i, &raw) in sym2raw.iter().enumerate() {
+			let (slice, flip) = (raw / N_FLIP, raw % N_FLIP);
+			for m in 0..N_MOVES_P1 {
+				let rawx = stbl.slice_move[slice * N_MOVES_P1 + m] as usize * N_FLIP + self.flip_move[flip * N_MOVES_P1 + m] as usize;
+				sym_move[i * N_MOVES_P1 + m] = self.flipslice_raw2sym[rawx];
+			}
+		}
+		(sym_move, selfsym)
+	}
+}
+
+/// Coordinates of a cube, seen along the three axes for the orientations and the slice
+#[derive(Clone, Copy)]
+struct OptCoord {
+	twst: [u16; 3],
+	flip: [u16; 3],
+	slice: [u16; 3],
+	cperm: u16,
+}
+
+impl OptCoord {
+	fn from_cubie(sctx: &StaticContext, src: &Cubie) -> Self {
+		let mut node = OptCoord {
+			twst: [0; 3],
+			flip: [0; 3],
+			slice: [0; 3],
+			cperm: src.get_cperm() as u16,
+		};
+		let mut cc1 = *src;
+		let mut cc2 = Cubie::new();
+		for i in 0..3 {
+			node.twst[i] = cc1.get_twst() as u16;
+			node.flip[i] = cc1.get_flip() as u16;
+			node.slice[i] = cc1.get_slice();
+			Cubie::corn_mult(&sctx.symurfi, &cc1, &mut cc2);
+			Cubie::edge_mult(&sctx.symurfi, &cc1, &mut cc2);
+			Cubie::corn_mult(&cc2, &sctx.symurf, &mut cc1);
+			Cubie::edge_mult(&cc2, &sctx.symurf, &mut cc1);
+		}
+		node
+	}
+
+	fn do_move(&self, stbl: &StaticTables, otbl: &OptimalTables, mv: usize) -> Self {
+		let mut node = *self;
+		for i in 0..3 {
+			let m = otbl.urf_movei[i][mv] as usize;
+			node.twst[i] = otbl.twst_move[self.twst[i] as usize * N_MOVES_P1 + m];
+			node.flip[i] = otbl.flip_move[self.flip[i] as usize * N_MOVES_P1 + m];
+			node.slice[i] = stbl.slice_move[self.slice[i] as usize * N_MOVES_P1 + m];
+		}
+		node.cperm = otbl.cperm_move[self.cperm as usize * N_MOVES_P1 + mv];
+		node
+	}
+
+	/// Lower bound of the number of moves to solve the cube
+	fn prun(&self, sctx: &StaticContext, stbl: &StaticTables, otbl: &OptimalTables) -> i8 {
+		let corn = esym2csym(stbl.eperm_raw2sym(sctx, self.cperm));
+		let mut prun = get_pruning(&otbl.cperm_twst_prun,
+			(corn >> 4) as usize * N_TWST + otbl.twst_conj[self.twst[0] as usize * 16 + (corn & 0xf) as usize] as usize);
+		for i in 0..3 {
+			let flipslice = otbl.flipslice_raw2sym[self.slice[i] as usize * N_FLIP + self.flip[i] as usize] as usize;
+			prun = prun.max(get_pruning(&otbl.flipslice_twst_prun,
+				(flipslice >> 4) * N_TWST + otbl.twst_conj[self.twst[i] as usize * 16 + (flipslice & 0xf)] as usize));
+		}
+		prun as i8
+	}
+}
+
+/// Iterative deepening A* search for a shortest solution in half turn metric
+pub(crate) struct OptimalContext {
+	cc: Cubie,
+	mv: [u8; 20],
+	deadline: Option<Instant>,
+	cancel: Option<CancelToken>,
+	abort_check: u32,
+	aborted: Option<SolveError>,
+}
+
+impl OptimalContext {
+	pub(crate) fn new(time_limit: Option<Duration>, cancel: Option<CancelToken>) -> Self {
+		OptimalContext {
+			cc: Cubie::new(),
+			mv: [0; 20],
+			deadline: time_limit.map(|limit| Instant::now() + limit),
+			cancel,
+			abort_check: 0,
+			aborted: None,
+		}
+	}
+
+	pub(crate) fn solve_cubie(&mut self, sctx: &StaticContext, stbl: &StaticTables, otbl: &OptimalTables,
+			cc: &Cubie, max_length: u8) -> Result<Algorithm, SolveError> {
+		self.cc = *cc;
+		let node = OptCoord::from_cubie(sctx, cc);
+		for maxl in node.prun(sctx, stbl, otbl)..=(max_length.min(20) as i8) {
+			if self.search(sctx, stbl, otbl, &node, 0, maxl, -1) {
+				return Ok(self.mv[..maxl as usize].iter().map(|&m| Move(m)).collect());
+			}
+			if let Some(err) = self.aborted {
+				return Err(err);
+			}
+		}
+		Err(SolveError::NoSolution)
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn search(&mut self, sctx: &StaticContext, stbl: &StaticTables, otbl: &OptimalTables,
+			node: &OptCoord, depth: usize, maxl: i8, lm: i8) -> bool {
+		if maxl == 0 {
+			return self.is_solved(sctx, depth);
+		}
+		if self.should_abort() {
+			return false;
+		}
+		for axis in (0..N_MOVES_P1 as i8).step_by(3) {
+			if axis == lm || axis == lm - 9 {
+				continue;
+			}
+			for power in 0..3 {
+				let m = (axis + power) as usize;
+				let next_node = node.do_move(stbl, otbl, m);
+				if next_node.prun(sctx, stbl, otbl) >= maxl {
+					continue;
+				}
+				self.mv[depth] = m as u8;
+				if self.search(sctx, stbl, otbl, &next_node, depth + 1, maxl - 1, axis) {
+					return true;
+				}
+			}
+		}
+		false
+	}
+
+	/// The coordinates do not cover the edge permutation, check the whole cube instead
+	fn is_solved(&self, sctx: &StaticContext, depth: usize) -> bool {
+		let mut cc = self.cc;
+		for &m in &self.mv[..depth] {
+			cc.apply_move(sctx, m as usize);
+		}
+		cc == Cubie::new()
+	}
+
+	fn should_abort(&mut self) -> bool {
+		self.abort_check = self.abort_check.wrapping_add(1);
+		if self.aborted.is_none() && self.abort_check & 0x3ff == 0 {
+			if let Some(deadline) = self.deadline {
+				if Instant::now() >= deadline {
+					self.aborted = Some(SolveError::Timeout);
+				}
+			}
+			if let Some(cancel) = &self.cancel {
+				if cancel.is_cancelled() {
+					self.aborted = Some(SolveError::Cancelled);
+				}
+			}
+		}
+		self.aborted.is_some()
+	}
+}
+
@@ -0,0 +1,75 @@
+//! Serde support, enabled by the `serde` feature
+//!
+//! [`Cube`], [`Move`] and [`Algorithm`] are serialized as strings in the usual notation, e.g.
+//! the 54 facelets of a cube and `"R U R' U'"`. Cubes can instead be serialized as their
+//! corner and edge arrays with [`cubie`]:
+//!
+//! ```
+//! use min2phase::Cube;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "min2phase::serialize::cubie")]
+//!     cube: Cube,
+//! }
+//! ```
+
+use alloc::string::String;
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::{Algorithm, Cube, Move};
+
+macro_rules! serde_as_str {
+	($($ty:ty),*) => {
+		$(
+			impl Serialize for $ty {
+				fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+					serializer.collect_str(self)
+				}
+			}
+
+			impl<'de> Deserialize<'de> for $ty {
+				fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+					String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+				}
+			}
+		)*
+	};
+}
+
+serde_as_str!(Move, Algorithm, Cube);
+
+/// Serialize a [`Cube`] as `{"ca": [..], "ea": [..]}`, the arrays of its 8 corners and 12 edges
+///
+/// As in the Java version of min2phase, each corner is `permutation | twist << 3` and each edge
+/// is `permutation << 1 | flip`. Deserializing fails if the arrays are not a solvable cube.
+pub mod cubie {
+	use super::*;
+	use crate::{Cubie, SolveError};
+
+	#[derive(Serialize, Deserialize)]
+	struct CubieArrays {
+		ca: [u8; 8],
+		ea: [u8; 12],
+	}
+
+	pub fn serialize<S: Serializer>(cube: &Cube, serializer: S) -> Result<S::Ok, S::Error> {
+		CubieArrays { ca: cube.cc.ca, ea: cube.cc.ea }.serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cube, D::Error> {
+		let arrays = CubieArrays::deserialize(deserializer)?;
+		if arrays.ca.iter().any(|&c| c >= 3 << 3) || arrays.ea.iter().any(|&e| e >= 12 << 1) {
+			return Err(de::Error::custom("corner or edge out of range"));
+		}
+		let cc = Cubie { ca: arrays.ca, ea: arrays.ea };
+		match cc.verify() {
+			0 => Ok(Cube { cc }),
+			verify => Err(de::Error::custom(SolveError::from_code(-verify).unwrap())),
+		}
+	}
+}
@@ -0,0 +1,121 @@
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::json::json_str;
+use crate::{Cube, SolveStats, Solver};
+
+/// Version of the [`serve`] protocol, incremented on any incompatible change
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Code of the responses to malformed requests
+const MALFORMED_REQUEST: i32 = -1;
+
+/// Solve the cubes requested line by line on `input`, writing one JSON line per request to `output`
+///
+/// This is the `min2phase serve` command, for long-running processes that load the tables once
+/// and are driven through pipes, e.g. from another language.
+///
+/// # Protocol, version 1
+///
+/// Once the tables are ready, the greeting `{"protocol":1,"ready":true}` is written.
+///
+/// Each request is a line with a facelet, see [`solve`](crate::solve), followed by optional
+/// `key=value` fields separated by spaces:
+/// - `max=N` - maximum solution length, the `max_length` of `solver` by default
+/// - `timeout=MS` - time limit in milliseconds, the `time_limit` of `solver` by default
+/// - `id=ID` - echoed in the response, `null` if missing, must not contain spaces
+///
+/// Blank lines and lines starting with `#` are ignored. Every other line gets exactly one
+/// response, in the order of the requests, flushed immediately:
+/// ```text
+/// UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB max=20 id=1
+/// {"id":"1","code":0,"solution":"","length":0,"probes":1}
+/// UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBF
+/// {"id":null,"code":1,"error":"invalid facelet, there is not exactly one facelet of each colour","probes":0}
+/// ```
+/// `code` is 0 on success, the [`SolveError::code`](crate::SolveError::code) of the failure
+/// otherwise, or -1 if the request itself is malformed. `solution` and `length` are only
+/// present on success, `error` only on failure. `probes` is the number of phase-2 searches,
+/// and is missing from the responses to malformed requests.
+///
+/// Fields may be added to the responses without changing the version, clients should ignore
+/// the fields they do not know. Serving ends at the end of `input`.
+pub fn serve<R: BufRead, W: Write>(solver: &Solver, input: R, mut output: W) -> io::Result<()> {
+	let mut current = solver.clone();
+	current.tables();
+	writeln!(output, "{{\"protocol\":{},\"ready\":true}}", PROTOCOL_VERSION)?;
+	output.flush()?;
+	for line in input.lines() {
+		let line = line?;
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let req = Request::parse(line);
+		let id = req.id.map_or("null".to_string(), json_str);
+		if let Some(msg) = req.error {
+			writeln!(output, "{{\"id\":{},\"code\":{},\"error\":{}}}", id, MALFORMED_REQUEST, json_str(&msg))?;
+			output.flush()?;
+			continue;
+		}
+		current.max_length = req.max_length.map_or(solver.max_length, |maxl| maxl.min(25));
+		current.time_limit = req.timeout.or(solver.time_limit);
+		let (ret, stats) = match Cube::from_facelet(req.facelet) {
+			Ok(cube) => current.search(&cube, None),
+			Err(err) => (Err(err), SolveStats::default()),
+		};
+		let probes = stats.probes;
+		match ret {
+			Ok(solution) => {
+				let alg = solution.to_algorithm();
+				writeln!(output, "{{\"id\":{},\"code\":0,\"solution\":{},\"length\":{},\"probes\":{}}}",
+					id, json_str(&alg.to_string()), alg.len(), probes)?;
+			},
+			Err(err) => {
+				writeln!(output, "{{\"id\":{},\"code\":{},\"error\":{},\"probes\":{}}}",
+					id, err.code(), json_str(&err.to_string()), probes)?;
+			},
+		}
+		output.flush()?;
+	}
+	Ok(())
+}
+
+struct Request<'a> {
+	facelet: &'a str,
+	max_length: Option<u8>,
+	timeout: Option<Duration>,
+	id: Option<&'a str>,
+	/// Why the request is malformed
+	error: Option<String>,
+}
+
+impl<'a> Request<'a> {
+	fn parse(line: &'a str) -> Self {
+		let mut fields = line.split_whitespace();
+		let mut req = Request {
+			facelet: fields.next().unwrap_or(""),
+			max_length: None,
+			timeout: None,
+			id: None,
+			error: None,
+		};
+		for field in fields {
+			let (key, value) = field.split_once('=').unwrap_or((field, ""));
+			let ok = match key {
+				"max" => value.parse().map(|maxl| req.max_length = Some(maxl)).is_ok(),
+				"timeout" => value.parse().map(|ms| req.timeout = Some(Duration::from_millis(ms))).is_ok(),
+				"id" => {
+					req.id = Some(value);
+					true
+				},
+				_ => false,
+			};
+			if !ok && req.error.is_none() {
+				req.error = Some(format!("invalid field {field}"));
+			}
+		}
+		req
+	}
+}
+
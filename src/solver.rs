@@ -0,0 +1,717 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::fmt;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicI8, AtomicU64, AtomicUsize};
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::optimal::OptimalContext;
+#[cfg(feature = "std")]
+use crate::tables::global_tables;
+use crate::{
+	Algorithm, Cube, IdaContext, SolveError, Solution, APPEND_LENGTH, INVERSE_SOLUTION,
+	MAX_PREMV_LEN, MIN_P1PRE_LEN, Tables, USE_SEPARATOR,
+};
+
+/// Two-phase solver with tunable search parameters
+///
+/// ```
+/// use min2phase::{Cube, Solver};
+///
+/// let solver = Solver::builder().max_length(21).build();
+/// let cube = Cube::random();
+/// let solution = solver.solve(&cube).unwrap();
+/// assert!(cube.apply(&solution).is_solved());
+/// ```
+///
+/// Solvers use the tables shared by the whole process, unless given their own with
+/// [`SolverBuilder::tables`]. The search state is kept between solves, so reusing a solver
+/// is cheaper than building a new one for each cube.
+///
+/// # Panics
+///
+/// Without the `std` feature, there are no shared tables: solving panics if the solver was
+/// not given its own.
+#[derive(Clone, Debug)]
+pub struct Solver {
+	pub(crate) max_length: u8,
+	min_probes: u64,
+	max_probes: u64,
+	verbose: u8,
+	max_premv_len: u8,
+	min_p1pre_len: u8,
+	#[cfg(feature = "std")]
+	pub(crate) time_limit: Option<Duration>,
+	#[cfg(feature = "std")]
+	threads: u8,
+	tables: Option<Arc<Tables>>,
+	#[cfg(feature = "std")]
+	ctx: ContextCache,
+}
+
+/// Search state of the last solve, reused by the next one
+///
+/// Concurrent solves with the same solver each get their own state, clones start empty.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct ContextCache(Mutex<Option<Box<IdaContext>>>);
+
+#[cfg(feature = "std")]
+impl ContextCache {
+	fn take(&self) -> Box<IdaContext> {
+		let cached = self.0.lock().map(|mut ctx| ctx.take()).unwrap_or(None);
+		cached.unwrap_or_else(|| Box::new(IdaContext::new()))
+	}
+
+	fn put(&self, ctx: Box<IdaContext>) {
+		if let Ok(mut cached) = self.0.lock() {
+			*cached = Some(ctx);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl Clone for ContextCache {
+	fn clone(&self) -> Self {
+		ContextCache::default()
+	}
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for ContextCache {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ContextCache").finish_non_exhaustive()
+	}
+}
+
+/// Builder of [`Solver`], created by [`Solver::builder`]
+#[derive(Clone, Debug)]
+pub struct SolverBuilder {
+	solver: Solver,
+}
+
+impl Solver {
+	pub fn builder() -> SolverBuilder {
+		SolverBuilder { solver: Solver::default() }
+	}
+
+	/// Solve the cube, the solution has at most `max_length` moves
+	pub fn solve(&self, cube: &Cube) -> Result<Algorithm, SolveError> {
+		self.search(cube, None).0.map(|solution| solution.to_algorithm())
+	}
+
+	/// Same as [`Solver::solve`], also return the statistics of the search, even if it failed
+	///
+	/// ```
+	/// use min2phase::{Cube, Solver};
+	///
+	/// let cube = Cube::from_moves("R U R' U' F2 D").unwrap();
+	/// let (solution, stats) = Solver::builder().build().solve_with_stats(&cube);
+	/// assert_eq!(solution.unwrap().len(), stats.phase1_length + stats.phase2_length + stats.pre_moves);
+	/// assert!(stats.probes >= 1 && stats.phase1_nodes >= 1);
+	/// ```
+	pub fn solve_with_stats(&self, cube: &Cube) -> (Result<Algorithm, SolveError>, SolveStats) {
+		let (ret, stats) = self.search(cube, None);
+		(ret.map(|solution| solution.to_algorithm()), stats)
+	}
+
+	/// Same as [`Solver::solve`], but the search stops with [`SolveError::Cancelled`] as soon as
+	/// `cancel` is cancelled, e.g. from another thread
+	pub fn solve_cancellable(&self, cube: &Cube, cancel: &CancelToken) -> Result<Algorithm, SolveError> {
+		self.search(cube, Some(cancel)).0.map(|solution| solution.to_algorithm())
+	}
+
+	/// Solve a cube represented in facelet, see [`solve`](crate::solve)
+	///
+	/// The solution is formatted as the legacy string API does, i.e. with a phase separator
+	/// or a length suffix if enabled.
+	pub fn solve_facelet(&self, facelet: &str) -> Result<String, SolveError> {
+		self.search(&Cube::from_facelet(facelet)?, None).0.map(|solution| solution.to_string())
+	}
+
+	/// Solve many cubes represented in facelet, spread over the worker threads of `options`
+	///
+	/// The results are in the order of `facelets` and formatted as [`Solver::solve_facelet`]
+	/// does. Each thread reuses its own search state, all of them share the tables.
+	/// [`SolverBuilder::threads`] is ignored, every cube is searched on a single thread.
+	/// ```no_run
+	/// use min2phase::{BatchOptions, Solver};
+	///
+	/// let facelets = ["UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"];
+	/// let results = Solver::builder().build().solve_batch(&facelets, &BatchOptions::new().workers(4));
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn solve_batch<S: AsRef<str> + Sync>(&self, facelets: &[S], options: &BatchOptions) -> Vec<Result<String, SolveError>> {
+		let tables = self.tables();
+		let workers = options.workers.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+		let workers = workers.min(facelets.len());
+		let next = AtomicUsize::new(0);
+		let mut results = vec![Err(SolveError::NoSolution); facelets.len()];
+		std::thread::scope(|scope| {
+			let handles: Vec<_> = (0..workers).map(|_| {
+				let mut worker = self.clone();
+				worker.threads = 1;
+				worker.tables = Some(tables.clone());
+				let next = &next;
+				scope.spawn(move || {
+					let mut solved = Vec::new();
+					loop {
+						let i = next.fetch_add(1, Ordering::Relaxed);
+						if i >= facelets.len() {
+							return solved;
+						}
+						let ret = Cube::from_facelet(facelets[i].as_ref())
+							.and_then(|cube| worker.search(&cube, options.cancel.as_ref()).0)
+							.map(|solution| solution.to_string());
+						solved.push((i, ret));
+					}
+				})
+			}).collect();
+			for handle in handles {
+				for (i, ret) in handle.join().unwrap() {
+					results[i] = ret;
+				}
+			}
+		});
+		results
+	}
+
+	/// Iterate over progressively shorter solutions of the cube
+	///
+	/// The first item is the first solution found, as returned by [`Solver::solve`], each
+	/// following item is shorter than the previous one. The iteration ends when no shorter
+	/// solution can be found, or when the time or probe limit is reached. `min_probes` is
+	/// ignored, as every improvement is returned as soon as it is found.
+	///
+	/// This is the counterpart of `next()` in the Java version of min2phase.
+	#[cfg(feature = "std")]
+	pub fn solutions(&self, cube: &Cube) -> Solutions {
+		let cancel = CancelToken::new();
+		let tables = self.tables();
+		let mut ctx = Box::new(IdaContext::new());
+		self.configure(&mut ctx, Some(&cancel));
+		ctx.min_probes = 0;
+		Solutions {
+			ctx,
+			tables,
+			cube: *cube,
+			max_length: self.max_length as i8,
+			started: false,
+			done: false,
+			start: Instant::now(),
+			cancel,
+		}
+	}
+
+	/// Iterate over distinct solutions of at most `max_length` moves
+	///
+	/// Solutions are reported in the order the two-phase search finds them, which roughly
+	/// follows the phase 1 length. Each phase 1 sequence is followed by every phase 2
+	/// sequence that solves the cube within `max_length`, shortest first. Sequences that are
+	/// equal after move cancellation are reported only once. The iteration ends when the search space is exhausted, or when
+	/// the time or probe limit is reached.
+	#[cfg(feature = "std")]
+	pub fn enumerate(&self, cube: &Cube) -> AllSolutions {
+		let cancel = CancelToken::new();
+		let tables = self.tables();
+		let mut ctx = Box::new(IdaContext::new());
+		self.configure(&mut ctx, Some(&cancel));
+		ctx.min_probes = 0;
+		ctx.enumerate = true;
+		AllSolutions {
+			ctx,
+			tables,
+			cube: *cube,
+			max_length: self.max_length as i8,
+			started: false,
+			done: false,
+			seen: HashSet::new(),
+			cancel,
+		}
+	}
+
+	/// Find a shortest solution in half turn metric, at most `max_length` moves
+	///
+	/// The first call builds extra pruning tables of about 70MB, which takes about 10 seconds
+	/// in release builds. The search time grows about tenfold with each move of the optimal
+	/// solution: up to 15 moves are solved within a second, 16 moves in about half a minute,
+	/// random cubes, which mostly need 18 moves, may take hours. See
+	/// [`SolverBuilder::time_limit`] to bound it. Other search parameters are ignored.
+	#[cfg(feature = "std")]
+	pub fn solve_optimal(&self, cube: &Cube) -> Result<Algorithm, SolveError> {
+		let tables = self.tables();
+		let otbl = tables.optimal();
+		OptimalContext::new(self.time_limit, None).solve_cubie(&tables.sctx, &tables.stbl, otbl, &cube.cc, self.max_length)
+	}
+
+	/// Solve the cube, also return the statistics of the search
+	pub(crate) fn search(&self, cube: &Cube, cancel: Option<&CancelToken>) -> (Result<Solution, SolveError>, SolveStats) {
+		let tables = self.tables();
+		#[cfg(feature = "std")]
+		let start = Instant::now();
+		#[cfg(feature = "std")]
+		let (ret, mut stats) = if self.threads > 1 {
+			self.search_parallel(&tables, cube, cancel)
+		} else {
+			self.search_single(&tables, cube, cancel)
+		};
+		#[cfg(not(feature = "std"))]
+		let (ret, mut stats) = self.search_single(&tables, cube, cancel);
+		if let Ok(solution) = &ret {
+			stats.phase1_length = solution.depth1 as usize;
+			stats.pre_moves = solution.premv_len as usize;
+			stats.phase2_length = (solution.length - solution.depth1 - solution.premv_len).max(0) as usize;
+			stats.urf_idx = solution.urf_idx;
+		}
+		#[cfg(feature = "std")]
+		{
+			stats.elapsed = start.elapsed();
+		}
+		(ret, stats)
+	}
+
+	fn search_single(&self, tables: &Tables, cube: &Cube, cancel: Option<&CancelToken>) -> (Result<Solution, SolveError>, SolveStats) {
+		#[cfg(feature = "std")]
+		let mut ctx = self.ctx.take();
+		// without std, there is no lock to keep the search state between solves
+		#[cfg(not(feature = "std"))]
+		let mut ctx = Box::new(IdaContext::new());
+		self.configure(&mut ctx, cancel);
+		let ret = ctx.solve_cubie(&tables.sctx, &tables.stbl, &cube.cc, self.max_length as i8);
+		let stats = SolveStats {
+			probes: ctx.probes,
+			phase1_nodes: ctx.nodes1,
+			phase2_nodes: ctx.nodes2,
+			..SolveStats::default()
+		};
+		#[cfg(feature = "std")]
+		self.ctx.put(ctx);
+		(ret, stats)
+	}
+
+	/// Search the orientations on separate threads, sharing the probes and the best length
+	#[cfg(feature = "std")]
+	fn search_parallel(&self, tables: &Tables, cube: &Cube, cancel: Option<&CancelToken>) -> (Result<Solution, SolveError>, SolveStats) {
+		let shared = Arc::new(SharedSearch {
+			target_length: AtomicI8::new(self.max_length as i8 + 1),
+			probes: AtomicU64::new(0),
+			found: AtomicBool::new(false),
+			stop: AtomicBool::new(false),
+		});
+		let threads = self.threads;
+		let results: Vec<_> = std::thread::scope(|scope| {
+			let handles: Vec<_> = (0..threads).map(|i| {
+				let mut ctx = Box::new(IdaContext::new());
+				self.configure(&mut ctx, cancel);
+				ctx.urf_mask = (0..6).filter(|urf_idx| urf_idx % threads == i).fold(0, |mask, urf_idx| mask | 1 << urf_idx);
+				ctx.shared = Some(shared.clone());
+				scope.spawn(move || {
+					let ret = ctx.solve_cubie(&tables.sctx, &tables.stbl, &cube.cc, self.max_length as i8);
+					(ret, ctx.nodes1, ctx.nodes2)
+				})
+			}).collect();
+			handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+		});
+		let mut stats = SolveStats {
+			probes: shared.probes.load(Ordering::Relaxed),
+			..SolveStats::default()
+		};
+		for (_, nodes1, nodes2) in &results {
+			stats.phase1_nodes += nodes1;
+			stats.phase2_nodes += nodes2;
+		}
+
+		let mut best: Option<Solution> = None;
+		let mut err = SolveError::NoSolution;
+		for (ret, _, _) in results {
+			match ret {
+				Ok(solution) => {
+					if best.map_or(true, |best| solution.length < best.length) {
+						best = Some(solution);
+					}
+				},
+				Err(SolveError::Cancelled) => return (Err(SolveError::Cancelled), stats),
+				// threads stopped by another one report NoSolution
+				Err(e) => {
+					if e == SolveError::Timeout || err == SolveError::NoSolution {
+						err = e;
+					}
+				},
+			}
+		}
+		(best.ok_or(err), stats)
+	}
+
+	/// The tables of this solver, built if needed, so that the time limit does not include building them
+	pub(crate) fn tables(&self) -> Arc<Tables> {
+		match &self.tables {
+			Some(tables) => tables.clone(),
+			#[cfg(feature = "std")]
+			None => global_tables().clone(),
+			#[cfg(not(feature = "std"))]
+			None => panic!("a solver needs its own tables without the std feature, see SolverBuilder::tables"),
+		}
+	}
+
+	fn configure(&self, ctx: &mut IdaContext, cancel: Option<&CancelToken>) {
+		ctx.enumerate = false;
+		ctx.min_probes = self.min_probes;
+		ctx.max_probes = self.max_probes;
+		ctx.max_premv_len = self.max_premv_len as i8;
+		ctx.min_p1pre_len = self.min_p1pre_len as i8;
+		#[cfg(feature = "std")]
+		{
+			ctx.deadline = self.time_limit.map(|limit| Instant::now() + limit);
+			ctx.shared = None;
+		}
+		ctx.cancel = cancel.cloned();
+		ctx.urf_mask = 0x3f;
+		ctx.solution.verbose = self.verbose;
+	}
+}
+
+impl Default for Solver {
+	fn default() -> Self {
+		Solver {
+			max_length: 21,
+			min_probes: 0,
+			max_probes: u64::MAX,
+			verbose: 0,
+			max_premv_len: MAX_PREMV_LEN as u8,
+			min_p1pre_len: MIN_P1PRE_LEN as u8,
+			#[cfg(feature = "std")]
+			time_limit: None,
+			#[cfg(feature = "std")]
+			threads: 1,
+			tables: None,
+			#[cfg(feature = "std")]
+			ctx: ContextCache::default(),
+		}
+	}
+}
+
+impl SolverBuilder {
+	/// Maximum number of moves of the solution, 21 by default, at most 25
+	pub fn max_length(mut self, maxl: u8) -> Self {
+		self.solver.max_length = maxl.min(25);
+		self
+	}
+
+	/// Keep searching for shorter solutions until `probes` phase-2 searches were tried, 0 by default
+	pub fn min_probes(mut self, probes: u64) -> Self {
+		self.solver.min_probes = probes;
+		self
+	}
+
+	/// Give up with [`SolveError::ProbeLimit`] if no solution was found after `probes`
+	/// phase-2 searches, unlimited by default
+	pub fn max_probes(mut self, probes: u64) -> Self {
+		self.solver.max_probes = probes;
+		self
+	}
+
+	/// Stop searching after `limit`, unlimited by default
+	///
+	/// The best solution found so far is returned, or [`SolveError::Timeout`] if there is none.
+	/// Together with [`SolverBuilder::min_probes`], the solver keeps improving the solution
+	/// until the time is up.
+	#[cfg(feature = "std")]
+	pub fn time_limit(mut self, limit: Duration) -> Self {
+		self.solver.time_limit = Some(limit);
+		self
+	}
+
+	/// Search the six orientations of the cube on up to `threads` threads, 1 by default
+	///
+	/// The threads share the best length found so far, so that a solution found by one of them
+	/// prunes the search of the others, and count their probes together. At most 6 threads are
+	/// used. [`Solver::solutions`] and [`Solver::enumerate`] always search on a single thread.
+	#[cfg(feature = "std")]
+	pub fn threads(mut self, threads: u8) -> Self {
+		self.solver.threads = threads.clamp(1, 6);
+		self
+	}
+
+	/// Search with `tables` instead of the tables shared by the whole process, required
+	/// without the `std` feature
+	pub fn tables(mut self, tables: Arc<Tables>) -> Self {
+		self.solver.tables = Some(tables);
+		self
+	}
+
+	/// Return the inverse of the solution, i.e. the moves generating the cube from the solved state
+	pub fn inverse_solution(mut self, enable: bool) -> Self {
+		self.set_verbose(INVERSE_SOLUTION, enable);
+		self
+	}
+
+	/// Separate phase 1 and phase 2 moves with a "." in [`Solver::solve_facelet`]
+	pub fn use_separator(mut self, enable: bool) -> Self {
+		self.set_verbose(USE_SEPARATOR, enable);
+		self
+	}
+
+	/// Append the length of the solution, e.g. "(21f)", in [`Solver::solve_facelet`]
+	pub fn append_length(mut self, enable: bool) -> Self {
+		self.set_verbose(APPEND_LENGTH, enable);
+		self
+	}
+
+	/// Maximum number of pre-moves applied before phase 1, 20 by default, at most 20
+	pub fn max_pre_moves(mut self, premv_len: u8) -> Self {
+		self.solver.max_premv_len = premv_len.min(MAX_PREMV_LEN as u8);
+		self
+	}
+
+	/// Minimum phase 1 length when pre-moves are applied, 7 by default
+	///
+	/// Smaller values allow more pre-moves, which slows down the search dramatically.
+	pub fn min_phase1_length_pre_moves(mut self, length: u8) -> Self {
+		self.solver.min_p1pre_len = length.min(20);
+		self
+	}
+
+	pub fn build(self) -> Solver {
+		self.solver
+	}
+
+	fn set_verbose(&mut self, flag: u8, enable: bool) {
+		if enable {
+			self.solver.verbose |= flag;
+		} else {
+			self.solver.verbose &= !flag;
+		}
+	}
+}
+
+/// Options of a [`Solver::solve_batch`] call
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct BatchOptions {
+	workers: Option<usize>,
+	cancel: Option<CancelToken>,
+}
+
+#[cfg(feature = "std")]
+impl BatchOptions {
+	/// One worker per available core, no cancellation
+	pub fn new() -> Self {
+		BatchOptions::default()
+	}
+
+	/// Number of worker threads, at least 1, one per available core by default
+	pub fn workers(mut self, workers: usize) -> Self {
+		self.workers = Some(workers.max(1));
+		self
+	}
+
+	/// Stop the batch when `cancel` is cancelled, the cubes not solved by then fail with
+	/// [`SolveError::Cancelled`]
+	pub fn cancel(mut self, cancel: CancelToken) -> Self {
+		self.cancel = Some(cancel);
+		self
+	}
+}
+
+/// Statistics of a solve, see [`Solver::solve_with_stats`]
+///
+/// The lengths and the orientation describe the solution found, they are 0 if the solve failed.
+/// The nodes of a parallel search are summed over its threads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveStats {
+	/// Number of phase-2 searches
+	pub probes: u64,
+	/// Number of nodes visited by phase 1
+	pub phase1_nodes: u64,
+	/// Number of nodes visited by phase 2
+	pub phase2_nodes: u64,
+	/// Moves found by phase 1, not counting the pre-moves
+	pub phase1_length: usize,
+	/// Moves found by phase 2, i.e. the rest of the solution, as moves may cancel where the parts meet
+	pub phase2_length: usize,
+	/// Orientation of the cube searched: 0, 1, 2 for the cube rotated along the URF diagonal,
+	/// 3, 4, 5 for its inverse
+	pub urf_idx: u8,
+	/// Number of pre-moves applied before phase 1
+	pub pre_moves: usize,
+	/// Time spent searching, zero without the `std` feature
+	pub elapsed: Duration,
+}
+
+/// A solution reported by [`Solutions`]
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Improvement {
+	pub solution: Algorithm,
+	/// Length of the solution in half turn metric
+	pub length: usize,
+	/// Number of phase-2 probes since the search started
+	pub probes: u64,
+	/// Time elapsed since the search started
+	pub elapsed: Duration,
+}
+
+/// Iterator over progressively shorter solutions, created by [`Solver::solutions`]
+#[cfg(feature = "std")]
+pub struct Solutions {
+	ctx: Box<IdaContext>,
+	tables: Arc<Tables>,
+	cube: Cube,
+	max_length: i8,
+	started: bool,
+	done: bool,
+	start: Instant,
+	cancel: CancelToken,
+}
+
+#[cfg(feature = "std")]
+impl Solutions {
+	/// Token to stop the search from another thread, the pending [`Iterator::next`] then returns `None`
+	pub fn cancel_token(&self) -> CancelToken {
+		self.cancel.clone()
+	}
+}
+
+#[cfg(feature = "std")]
+impl Iterator for Solutions {
+	type Item = Improvement;
+
+	fn next(&mut self) -> Option<Improvement> {
+		if self.done {
+			return None;
+		}
+		let ret = if self.started {
+			self.ctx.next_solution(&self.tables.sctx, &self.tables.stbl)
+		} else {
+			self.started = true;
+			self.ctx.solve_cubie(&self.tables.sctx, &self.tables.stbl, &self.cube.cc, self.max_length)
+		};
+		match ret {
+			Ok(solution) => Some(Improvement {
+				solution: solution.to_algorithm(),
+				length: solution.length as usize,
+				probes: self.ctx.probes,
+				elapsed: self.start.elapsed(),
+			}),
+			Err(_) => {
+				self.done = true;
+				None
+			}
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::iter::FusedIterator for Solutions {}
+
+/// A solution reported by [`AllSolutions`]
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumeratedSolution {
+	pub solution: Algorithm,
+	/// Orientation of the cube searched: 0, 1, 2 for the cube rotated along the URF diagonal,
+	/// 3, 4, 5 for its inverse
+	pub urf_idx: u8,
+	/// Number of pre-moves applied before phase 1
+	pub pre_moves: usize,
+}
+
+/// Iterator over distinct solutions, created by [`Solver::enumerate`]
+#[cfg(feature = "std")]
+pub struct AllSolutions {
+	ctx: Box<IdaContext>,
+	tables: Arc<Tables>,
+	cube: Cube,
+	max_length: i8,
+	started: bool,
+	done: bool,
+	seen: HashSet<Algorithm>,
+	cancel: CancelToken,
+}
+
+#[cfg(feature = "std")]
+impl AllSolutions {
+	/// Token to stop the search from another thread, the pending [`Iterator::next`] then returns `None`
+	pub fn cancel_token(&self) -> CancelToken {
+		self.cancel.clone()
+	}
+}
+
+#[cfg(feature = "std")]
+impl Iterator for AllSolutions {
+	type Item = EnumeratedSolution;
+
+	fn next(&mut self) -> Option<EnumeratedSolution> {
+		while !self.done {
+			let ret = if self.started {
+				self.ctx.next_solution(&self.tables.sctx, &self.tables.stbl)
+			} else {
+				self.started = true;
+				self.ctx.solve_cubie(&self.tables.sctx, &self.tables.stbl, &self.cube.cc, self.max_length)
+			};
+			match ret {
+				Ok(solution) => {
+					let alg = solution.to_algorithm();
+					if self.seen.insert(alg.clone()) {
+						return Some(EnumeratedSolution {
+							solution: alg,
+							urf_idx: solution.urf_idx,
+							pre_moves: solution.premv_len as usize,
+						});
+					}
+				},
+				Err(_) => self.done = true,
+			}
+		}
+		None
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::iter::FusedIterator for AllSolutions {}
+
+/// State shared by the threads of a parallel search, see [`SolverBuilder::threads`]
+#[cfg(feature = "std")]
+pub(crate) struct SharedSearch {
+	/// Length of the best solution found so far plus one, as `IdaContext::target_length`
+	pub(crate) target_length: AtomicI8,
+	pub(crate) probes: AtomicU64,
+	pub(crate) found: AtomicBool,
+	/// Set by the thread that ends the search, the others then stop as well
+	pub(crate) stop: AtomicBool,
+}
+
+/// Shared flag to cancel a running solve, see [`Solver::solve_cancellable`]
+///
+/// Clones refer to the same flag, so a token can be handed to another thread and cancelled there.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+	cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+	pub fn new() -> Self {
+		CancelToken::default()
+	}
+
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+}
@@ -0,0 +1,755 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::cubie::Cubie;
+
+pub(crate) const N_FLIP     : usize =  2048;
+pub(crate) const N_FLIP_SYM : usize =   336;
+pub(crate) const N_TWST     : usize =  2187;
+pub(crate) const N_TWST_SYM : usize =   324;
+pub(crate) const N_SLICE    : usize =   495;
+pub(crate) const N_PERM     : usize = 40320;
+pub(crate) const N_PERM_SYM : usize =  2768;
+pub(crate) const N_MPERM    : usize =    24;
+pub(crate) const N_CCOMB    : usize =    70;
+
+pub(crate) const N_MOVES_P1 : usize =    18;
+pub(crate) const N_MOVES_P2 : usize =    10;
+
+pub(crate) static P2MOVES: [u8; 18] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16, 3, 5, 6, 8, 12, 14, 15, 17];
+
+/// Progress of the table construction, see [`Tables::build_with_progress`](crate::Tables::build_with_progress)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Progress {
+	/// The breadth-first search of the pruning table `table` reached `depth` moves,
+	/// `entries` entries of the table are filled so far
+	Depth { table: &'static str, depth: u32, entries: u64 },
+	/// The table `table` is complete
+	Done { table: &'static str },
+}
+
+/// Trade-off between the memory taken by the [`Tables`](crate::Tables) and the speed of the search
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TableProfile {
+	/// About 570KB, the tables of the Java version of min2phase
+	#[default]
+	Standard,
+	/// About 240KB, for microcontrollers with 256KB of RAM, building the tables peaks at about
+	/// the same and solving allocates less than 1KB more
+	///
+	/// The phase 2 move tables are replaced by moves of cubies, phase 1 is only pruned by
+	/// twist x slice and phase 2 by the permutations of the corners and of the edges, each
+	/// with the middle edges. Solving takes about 40 times longer.
+	LowMemory,
+	/// About 900KB, the standard tables and a twist x flip pruning table for phase 1, as
+	/// `USE_TWIST_FLIP_PRUN` of the Java version of min2phase
+	///
+	/// Phase 1 visits about 2.6 times fewer nodes, a solve takes about 20-35% less time. The
+	/// probes are the same, compare `min2phase bench --seed 1` with
+	/// `min2phase bench --seed 1 --profile twist-flip`.
+	TwistFlip,
+}
+
+pub(crate) fn esym2csym(esym: u16) -> u16 {
+	esym ^ (0x00dddd00u32 >> ((esym & 0xf) << 1) & 3) as u16
+}
+
+/// Quarter turns of the faces U, R, F, D, L, B
+pub(crate) static MOVE_BASE: [Cubie; 6] = [
+	Cubie {ca: [3, 0, 1, 2, 4, 5, 6, 7], ea: [6, 0, 2, 4, 8, 10, 12, 14, 16, 18, 20, 22]},
+	Cubie {ca: [20, 1, 2, 8, 15, 5, 6, 19], ea: [16, 2, 4, 6, 22, 10, 12, 14, 8, 18, 20, 0]},
+	Cubie {ca: [9, 21, 2, 3, 16, 12, 6, 7], ea: [0, 19, 4, 6, 8, 17, 12, 14, 3, 11, 20, 22]},
+	Cubie {ca: [0, 1, 2, 3, 5, 6, 7, 4], ea: [0, 2, 4, 6, 10, 12, 14, 8, 16, 18, 20, 22]},
+	Cubie {ca: [0, 10, 22, 3, 4, 17, 13, 7], ea: [0, 2, 20, 6, 8, 10, 18, 14, 16, 4, 12, 22]},
+	Cubie {ca: [0, 1, 11, 23, 4, 5, 18, 14], ea: [0, 2, 4, 23, 8, 10, 12, 21, 16, 18, 7, 15]}
+];
+
+pub(crate) struct StaticContext {
+	pub(crate) movecube: [Cubie; 18],
+	pub(crate) symcube: [Cubie; 16],
+	pub(crate) symmult: [[u8; 16]; 16],
+	pub(crate) symmuli: [[u8; 16]; 16],
+	pub(crate) symmove: [[u8; 8]; N_MOVES_P1],
+	pub(crate) symmove2: [[u8; 16]; N_MOVES_P1],
+	pub(crate) canon_masks2: [u16; 11],
+	pub(crate) symurf: Cubie,
+	pub(crate) symurfi: Cubie,
+}
+
+impl StaticContext {
+	pub(crate) fn box_new() -> Box<Self> {
+		let mut sctx = Box::new(StaticContext {
+			movecube: [Cubie::new(); 18],
+			symcube: [Cubie::new(); 16],
+			symmult: [[0; 16]; 16],
+			symmuli: [[0; 16]; 16],
+			symmove: [[0; 8]; 18],
+			symmove2: [[0; 16]; 18],
+			canon_masks2: [0; 11],
+			symurf: Cubie::new(),
+			symurfi: Cubie::new(),
+		});
+		sctx.init();
+		sctx
+	}
+
+	#[allow(clippy::needless_range_loop)]
+	fn init(&mut self) {
+		for i in 0..18 {
+			if i % 3 == 0 {
+				self.movecube[i] = MOVE_BASE[i / 3];
+			} else {
+				let mut cc = Cubie::new();
+				Cubie::corn_mult(&self.movecube[i - 1], &MOVE_BASE[i / 3], &mut cc);
+				Cubie::edge_mult(&self.movecube[i - 1], &MOVE_BASE[i / 3], &mut cc);
+				self.movecube[i] = cc;
+			}
+		}
+
+		let u4 = Cubie {
+			ca: [3, 0, 1, 2, 7, 4, 5, 6],
+			ea: [6, 0, 2, 4, 14, 8, 10, 12, 23, 17, 19, 21]
+		};
+		let lr2 = Cubie {
+			ca: [25, 24, 27, 26, 29, 28, 31, 30],
+			ea: [4, 2, 0, 6, 12, 10, 8, 14, 18, 16, 22, 20]
+		};
+		let f2 = Cubie {
+			ca: [5, 4, 7, 6, 1, 0, 3, 2],
+			ea: [12, 10, 8, 14, 4, 2, 0, 6, 18, 16, 22, 20]
+		};
+
+		let mut cc = Cubie::new();
+		let mut cd = Cubie::new();
+
+		for i in 0..16 {
+			self.symcube[i] = cc;
+			Cubie::corn_mult(&cc, &u4, &mut cd);
+			Cubie::edge_mult(&cc, &u4, &mut cd);
+			cc = cd;
+			if i % 4 == 3 {
+				Cubie::corn_mult(&cc, &lr2, &mut cd);
+				Cubie::edge_mult(&cc, &lr2, &mut cd);
+				cc = cd;
+			}
+			if i % 8 == 7 {
+				Cubie::corn_mult(&cc, &f2, &mut cd);
+				Cubie::edge_mult(&cc, &f2, &mut cd);
+				cc = cd;
+			}
+		}
+
+		self.symurf = Cubie{ca: [8, 20, 13, 17, 19, 15, 22, 10], ea: [3, 16, 11, 18, 7, 22, 15, 20, 1, 9, 13, 5]};
+		Cubie::corn_mult(&self.symurf, &self.symurf, &mut self.symurfi);
+		Cubie::edge_mult(&self.symurf, &self.symurf, &mut self.symurfi);
+
+		for i in 0..16 {
+			for j in 0..16 {
+				Cubie::corn_mult(&self.symcube[i], &self.symcube[j], &mut cc);
+				Cubie::edge_mult(&self.symcube[i], &self.symcube[j], &mut cc);
+				for k in 0..16 {
+					if Cubie::cmp(&cc, &self.symcube[k]) == 0 {
+						self.symmult[i][j] = k as u8;
+						self.symmuli[k][j] = i as u8;
+					}
+				}
+			}
+		}
+
+		let mut p2moves_imap = [0; 18];
+		for i in 0..18 {
+			p2moves_imap[P2MOVES[i] as usize] = i;
+		}
+
+		for i in 0..18 {
+			for j in 0..16 {
+				Cubie::corn_mult(&self.symcube[j], &self.movecube[i], &mut cc);
+				Cubie::corn_mult(&cc, &self.symcube[self.symmuli[0][j] as usize], &mut cd);
+				Cubie::edge_mult(&self.symcube[j], &self.movecube[i], &mut cc);
+				Cubie::edge_mult(&cc, &self.symcube[self.symmuli[0][j] as usize], &mut cd);
+				for k in 0..18 {
+					if Cubie::cmp(&self.movecube[k], &cd) == 0 {
+						self.symmove2[p2moves_imap[i]][j] = p2moves_imap[k] as u8;
+						if j % 2 == 0 {
+							self.symmove[i][j / 2] = k as u8;
+						}
+						break;
+					}
+				}
+			}
+		}
+
+		for i in 0..10 {
+			let ix = P2MOVES[i] as usize / 3;
+			self.canon_masks2[i] = 0;
+			for j in 0..10 {
+				let jx = P2MOVES[j] as usize / 3;
+				self.canon_masks2[i] |= if (ix == jx) || ((ix % 3 == jx % 3) && (ix >= jx)) { 1 } else { 0 } << j;
+			}
+		}
+		self.canon_masks2[10] = 0;
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+fn init_sym2raw(
+	sctx: &StaticContext, n_raw: usize, coord: usize,
+	sym2raw: &mut [u16], raw2sym: &mut [u16], selfsym: &mut [u16], raw_sym: &mut [u32],
+	progress: &mut dyn FnMut(Progress),
+) -> usize {
+	let mut c = Cubie::new();
+	let mut e = Cubie::new();
+	let mut d = Cubie::new();
+	let sym_inc = if coord >= 2 { 1 } else { 2 };
+	let sym_shift = if coord >= 2 { 0 } else { 1 };
+	// raw2sym is left out of the low-memory profile, a bitmap is enough to find the representatives
+	let mut visited = vec![0u32; n_raw / 32 + 1];
+	let mut count = 0;
+	for i in 0..n_raw {
+		if (visited[i >> 5] >> (i & 31)) & 1 != 0 {
+			continue;
+		}
+		match coord {
+			0 => c.set_flip(i as u16),
+			1 => c.set_twst(i as u16),
+			2 => c.set_eperm(i as u16),
+			_ => unreachable!(),
+		}
+		for s in (0..16).step_by(sym_inc) {
+			if coord == 1 {
+				Cubie::corn_mult(&sctx.symcube[sctx.symmuli[0][s] as usize], &c, &mut e);
+				Cubie::corn_mult(&e, &sctx.symcube[s], &mut d);
+			} else {
+				Cubie::edge_mult(&sctx.symcube[sctx.symmuli[0][s] as usize], &c, &mut e);
+				Cubie::edge_mult(&e, &sctx.symcube[s], &mut d);
+			}
+			let idx = match coord {
+				0 => d.get_flip(),
+				1 => d.get_twst(),
+				2 => d.get_eperm(),
+				_ => unreachable!(),
+			};
+			if idx == i as i32 {
+				selfsym[count] |= 1 << (s >> sym_shift);
+			}
+			visited[idx as usize >> 5] |= 1 << (idx & 31);
+			if !raw2sym.is_empty() {
+				raw2sym[idx as usize] = ((count << 4 | s) >> sym_shift) as u16;
+			}
+			if !raw_sym.is_empty() {
+				let shift = (idx & 7) << 2;
+				raw_sym[idx as usize >> 3] = raw_sym[idx as usize >> 3] & !(0xf << shift) | ((s >> sym_shift) as u32) << shift;
+			}
+		}
+		sym2raw[count] = i as u16;
+		count += 1;
+	}
+	let table = ["flip_sym2raw", "twst_sym2raw", "eperm_sym2raw"][coord];
+	#[cfg(feature = "log")]
+	log::debug!(table, count; "{} has {} representatives", table, count);
+	progress(Progress::Done { table });
+	count
+}
+
+/// The tables of the two-phase search, see [`TableProfile`] for the tables left out, which are empty
+pub(crate) struct StaticTables {
+	pub(crate) profile: TableProfile,
+	pub(crate) perm_sym_inv : Box<[u16]>,
+	pub(crate) cperm2comb   : Box<[u8]>,
+	pub(crate) flip_sym2raw : Box<[u16]>,
+	pub(crate) flip_raw2sym : Box<[u16]>,
+	pub(crate) flip_selfsym : Box<[u16]>,
+	pub(crate) twst_sym2raw : Box<[u16]>,
+	pub(crate) twst_raw2sym : Box<[u16]>,
+	pub(crate) twst_selfsym : Box<[u16]>,
+	pub(crate) eperm_sym2raw: Box<[u16]>,
+	pub(crate) eperm_raw2sym: Box<[u16]>,
+	/// Only the symmetries of `eperm_raw2sym`, 4 bits each, in the low-memory profile
+	pub(crate) eperm_rawsym: Box<[u32]>,
+	pub(crate) eperm_selfsym: Box<[u16]>,
+	pub(crate) flip_move    : Box<[u16]>,
+	pub(crate) twst_move    : Box<[u16]>,
+	pub(crate) slice_move   : Box<[u16]>,
+	pub(crate) slice_conj   : Box<[u16]>,
+	pub(crate) cperm_move   : Box<[u16]>,
+	pub(crate) eperm_move   : Box<[u16]>,
+	pub(crate) mperm_move   : Box<[u16]>,
+	pub(crate) mperm_conj   : Box<[u16]>,
+	pub(crate) ccomb_move   : Box<[u16]>,
+	pub(crate) ccomb_conj   : Box<[u16]>,
+	pub(crate) slice_flip_prun : Box<[u32]>,
+	pub(crate) slice_twst_prun : Box<[u32]>,
+	pub(crate) ccomb_eperm_prun: Box<[u32]>,
+	pub(crate) mperm_cperm_prun: Box<[u32]>,
+	/// Only in the low-memory profile, instead of `ccomb_eperm_prun`
+	pub(crate) mperm_eperm_prun: Box<[u32]>,
+	/// Raw flip of each flip conjugated by each of the 8 symmetries, only with `twst_flip_prun`
+	pub(crate) flip_s2rf    : Box<[u16]>,
+	/// Only in the twist-flip profile
+	pub(crate) twst_flip_prun: Box<[u32]>,
+}
+
+#[allow(clippy::needless_range_loop)]
+fn init_move_tables(sctx: &StaticContext, stbl: &mut StaticTables) {
+	let mut c = Cubie::new();
+	c.reset();
+
+	for i in 0..N_FLIP_SYM {
+		c.set_flip(stbl.flip_sym2raw[i]);
+		for j in 0..N_MOVES_P1 {
+			let mut d = Cubie::new();
+			Cubie::edge_mult(&c, &sctx.movecube[j], &mut d);
+			stbl.flip_move[i * N_MOVES_P1 + j] = stbl.flip_raw2sym[d.get_flip() as usize];
+		}
+	}
+
+	if !stbl.flip_s2rf.is_empty() {
+		let mut d = Cubie::new();
+		let mut e = Cubie::new();
+		for i in 0..N_FLIP_SYM {
+			c.set_flip(stbl.flip_sym2raw[i]);
+			for s in 0..8 {
+				Cubie::edge_mult(&sctx.symcube[sctx.symmuli[0][s << 1] as usize], &c, &mut e);
+				Cubie::edge_mult(&e, &sctx.symcube[s << 1], &mut d);
+				stbl.flip_s2rf[i * 8 + s] = d.get_flip() as u16;
+			}
+		}
+	}
+
+	for i in 0..N_TWST_SYM {
+		c.set_twst(stbl.twst_sym2raw[i]);
+		for j in 0..N_MOVES_P1 {
+			let mut d = Cubie::new();
+			Cubie::corn_mult(&c, &sctx.movecube[j], &mut d);
+			stbl.twst_move[i * N_MOVES_P1 + j] = stbl.twst_raw2sym[d.get_twst() as usize];
+		}
+	}
+
+	for i in 0..N_SLICE {
+		c.set_slice(i as i32);
+		for j in 0..N_MOVES_P1 {
+			let mut d = Cubie::new();
+			Cubie::edge_mult(&c, &sctx.movecube[j], &mut d);
+			stbl.slice_move[i * N_MOVES_P1 + j] = d.get_slice();
+		}
+		for j in 0..8 {
+			let mut e = Cubie::new();
+			let mut d = Cubie::new();
+			Cubie::edge_mult(&sctx.symcube[j << 1], &c, &mut e);
+			Cubie::edge_mult(&e, &sctx.symcube[j << 1], &mut d);
+			stbl.slice_conj[i * 8 + j] = d.get_slice();
+		}
+	}
+
+	c.reset();
+	// left out of the low-memory profile, see StaticTables::cperm_move_p2
+	if stbl.profile != TableProfile::LowMemory {
+		for i in 0..N_PERM_SYM {
+			c.set_cperm(stbl.eperm_sym2raw[i]);
+			c.set_eperm(stbl.eperm_sym2raw[i]);
+			for j in 0..N_MOVES_P2 {
+				let mut d = Cubie::new();
+				Cubie::corn_mult(&c, &sctx.movecube[P2MOVES[j] as usize], &mut d);
+				Cubie::edge_mult(&c, &sctx.movecube[P2MOVES[j] as usize], &mut d);
+				stbl.cperm_move[i * N_MOVES_P2 + j] = esym2csym(stbl.eperm_raw2sym[d.get_cperm() as usize]);
+				stbl.eperm_move[i * N_MOVES_P2 + j] = stbl.eperm_raw2sym[d.get_eperm() as usize];
+			}
+			let mut d = Cubie::new();
+			Cubie::inv(&c, &mut d);
+			stbl.perm_sym_inv[i] = stbl.eperm_raw2sym[d.get_eperm() as usize];
+			stbl.cperm2comb[i] = c.get_ccomb() as u8;
+		}
+	}
+
+	for i in 0..N_MPERM {
+		c.set_mperm(i as u16);
+		for j in 0..N_MOVES_P2 {
+			let mut d = Cubie::new();
+			Cubie::edge_mult(&c, &sctx.movecube[P2MOVES[j] as usize], &mut d);
+			stbl.mperm_move[i * N_MOVES_P2 + j] = d.get_mperm() as u16;
+		}
+		for j in 0..16 {
+			let mut e = Cubie::new();
+			let mut d = Cubie::new();
+			Cubie::edge_mult(&sctx.symcube[j], &c, &mut e);
+			Cubie::edge_mult(&e, &sctx.symcube[sctx.symmuli[0][j] as usize], &mut d);
+			stbl.mperm_conj[i * 16 + j] = d.get_mperm() as u16;
+		}
+	}
+
+	if stbl.profile != TableProfile::LowMemory {
+		for i in 0..N_CCOMB {
+			c.set_ccomb(i as i32);
+			for j in 0..N_MOVES_P2 {
+				let mut d = Cubie::new();
+				Cubie::corn_mult(&c, &sctx.movecube[P2MOVES[j] as usize], &mut d);
+				stbl.ccomb_move[i * N_MOVES_P2 + j] = d.get_ccomb() as u16;
+			}
+			for j in 0..16 {
+				let mut e = Cubie::new();
+				let mut d = Cubie::new();
+				Cubie::corn_mult(&sctx.symcube[j], &c, &mut e);
+				Cubie::corn_mult(&e, &sctx.symcube[sctx.symmuli[0][j] as usize], &mut d);
+				stbl.ccomb_conj[i * 16 + j] = d.get_ccomb() as u16;
+			}
+		}
+	}
+}
+
+fn set_pruning(table: &mut [u32], index: usize, value: u32) {
+	table[index >> 3] ^= value << ((index & 7) << 2);
+}
+
+pub(crate) fn get_pruning(table: &[u32], index: usize) -> u32 {
+	(table[index >> 3] >> ((index & 7) << 2)) & 0xf
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_raw_sym_prun<S: Copy + Into<u32>>(
+	prun_table: &mut [u32],
+	raw_move: &[u16],
+	raw_conj: &[u16],
+	sym_move: &[S],
+	sym_selfsym: &[u16],
+	n_raw: usize,
+	n_sym: usize,
+	prun_flag: usize,
+	name: &'static str,
+	progress: &mut dyn FnMut(Progress),
+) {
+	let sym_shift: usize = prun_flag & 0xf;
+	let sym_e2c_magic: usize = if (prun_flag >> 4) & 1 == 1 { 0x00DDDD00 } else { 0 };
+	let is_phase2: usize = if (prun_flag >> 5) & 1 == 1 { 1 } else { 0 };
+	let inv_depth: usize = (prun_flag >> 8) & 0xf;
+	let max_depth: usize = (prun_flag >> 12) & 0xf;
+
+	let sym_mask: usize = (1 << sym_shift) - 1;
+	let n_entries: usize = n_raw * n_sym;
+	let n_moves: usize = if is_phase2 != 0 { N_MOVES_P2 } else { N_MOVES_P1 };
+
+	let mut depth: usize = 0;
+	let mut done: usize = 1;
+
+	prun_table[..n_entries / 8 + 1].fill(0xffffffff);
+	set_pruning(prun_table, 0, 0xf);
+
+	while depth < max_depth {
+		let inv = depth > inv_depth;
+		let select = (if inv { 0xf } else { depth }) as u32;
+		let check = (if inv { depth } else { 0xf }) as u32;
+		depth += 1;
+		let xor_val = (depth ^ 0xf) as u32;
+		let mut val: u32 = 0;
+		let mut i = 0;
+		while i < n_entries {
+			if (i & 7) == 0 {
+				val = prun_table[i >> 3];
+				if !inv && val == 0xffffffff {
+					i += 8;
+					continue;
+				}
+			}
+
+			if (val & 0xf) != select {
+				i += 1;
+				val >>= 4;
+				continue;
+			}
+
+			let raw = i % n_raw;
+			let sym = i / n_raw;
+
+			for m in 0..n_moves {
+				let symx = sym_move[sym * n_moves + m].into() as usize;
+				let rawx = raw_conj[(raw_move[raw * n_moves + m] as usize) << sym_shift | (symx & sym_mask)] as usize;
+				let symx = symx >> sym_shift;
+				let idx = symx * n_raw + rawx;
+				let prun = get_pruning(prun_table, idx);
+
+				if prun != check {
+					continue;
+				}
+
+				done += 1;
+				if inv {
+					set_pruning(prun_table, i, xor_val);
+					break;
+				}
+
+				set_pruning(prun_table, idx, xor_val);
+				let idx = idx - rawx;
+
+				for j in 1..=15 {
+					let ssmask = sym_selfsym[symx];
+					if (ssmask >> j) & 1 == 0 {
+						continue;
+					}
+					let idxx = idx + raw_conj[(rawx << sym_shift) | (j ^ (sym_e2c_magic >> (j << 1) & 3))] as usize;
+					if get_pruning(prun_table, idxx) == check {
+						set_pruning(prun_table, idxx, xor_val);
+						done += 1;
+					}
+				}
+			}
+			i += 1;
+			val >>= 4;
+		}
+		#[cfg(feature = "log")]
+		log::debug!(table = name, depth, entries = done; "{} reached depth {}, {} entries", name, depth, done);
+		progress(Progress::Depth { table: name, depth: depth as u32, entries: done as u64 });
+	}
+	progress(Progress::Done { table: name });
+}
+
+impl StaticTables {
+	/// Tables of `profile` with all entries zeroed, except the pruning tables which are
+	/// allocated by [`StaticTables::alloc_prun`]
+	///
+	/// Each table is allocated on its own, the tables are never built on the stack.
+	pub(crate) fn box_zeroed(profile: TableProfile) -> Box<Self> {
+		let full = profile != TableProfile::LowMemory;
+		let optional = |len: usize| if full { len } else { 0 };
+		Box::new(StaticTables {
+			profile,
+			perm_sym_inv : zeroed(optional(N_PERM_SYM)),
+			cperm2comb   : zeroed(optional(N_PERM_SYM)),
+			flip_sym2raw : zeroed(N_FLIP_SYM),
+			flip_raw2sym : zeroed(N_FLIP),
+			flip_selfsym : zeroed(N_FLIP_SYM),
+			twst_sym2raw : zeroed(N_TWST_SYM),
+			twst_raw2sym : zeroed(N_TWST),
+			twst_selfsym : zeroed(N_TWST_SYM),
+			eperm_sym2raw: zeroed(N_PERM_SYM),
+			eperm_raw2sym: zeroed(optional(N_PERM)),
+			eperm_rawsym : zeroed(if full { 0 } else { N_PERM / 8 }),
+			eperm_selfsym: zeroed(N_PERM_SYM),
+			flip_move    : zeroed(N_FLIP_SYM * N_MOVES_P1),
+			twst_move    : zeroed(N_TWST_SYM * N_MOVES_P1),
+			slice_move   : zeroed(N_SLICE * N_MOVES_P1),
+			slice_conj   : zeroed(N_SLICE * 8),
+			cperm_move   : zeroed(optional(N_PERM_SYM * N_MOVES_P2)),
+			eperm_move   : zeroed(optional(N_PERM_SYM * N_MOVES_P2)),
+			mperm_move   : zeroed(N_MPERM * N_MOVES_P2),
+			mperm_conj   : zeroed(N_MPERM * 16),
+			ccomb_move   : zeroed(optional(N_CCOMB * N_MOVES_P2)),
+			ccomb_conj   : zeroed(optional(N_CCOMB * 16)),
+			slice_flip_prun : Box::default(),
+			slice_twst_prun : Box::default(),
+			ccomb_eperm_prun: Box::default(),
+			mperm_cperm_prun: Box::default(),
+			mperm_eperm_prun: Box::default(),
+			flip_s2rf    : zeroed(if profile == TableProfile::TwistFlip { N_FLIP_SYM * 8 } else { 0 }),
+			twst_flip_prun: Box::default(),
+		})
+	}
+
+	/// Allocate the pruning tables of the profile
+	pub(crate) fn alloc_prun(&mut self) {
+		self.slice_twst_prun = zeroed(N_SLICE * N_TWST_SYM / 8 + 1);
+		self.mperm_cperm_prun = zeroed(N_MPERM * N_PERM_SYM / 8 + 1);
+		if self.profile == TableProfile::LowMemory {
+			self.mperm_eperm_prun = zeroed(N_MPERM * N_PERM_SYM / 8 + 1);
+		} else {
+			self.slice_flip_prun = zeroed(N_SLICE * N_FLIP_SYM / 8 + 1);
+			self.ccomb_eperm_prun = zeroed(N_CCOMB * N_PERM_SYM / 8 + 1);
+		}
+		if self.profile == TableProfile::TwistFlip {
+			self.twst_flip_prun = zeroed(N_FLIP * N_TWST_SYM / 8 + 1);
+		}
+	}
+
+	/// Symmetry and move tables
+	pub(crate) fn init_move(&mut self, sctx: &StaticContext, progress: &mut dyn FnMut(Progress)) {
+		init_sym2raw(sctx, N_FLIP, 0, &mut self.flip_sym2raw, &mut self.flip_raw2sym, &mut self.flip_selfsym, &mut [], progress);
+		init_sym2raw(sctx, N_TWST, 1, &mut self.twst_sym2raw, &mut self.twst_raw2sym, &mut self.twst_selfsym, &mut [], progress);
+		init_sym2raw(sctx, N_PERM, 2, &mut self.eperm_sym2raw, &mut self.eperm_raw2sym, &mut self.eperm_selfsym, &mut self.eperm_rawsym, progress);
+		init_move_tables(sctx, self);
+		progress(Progress::Done { table: "move_tables" });
+	}
+
+	/// The tables computed by [`StaticTables::init_prun`]
+	#[cfg_attr(not(feature = "embedded-tables"), allow(dead_code))]
+	pub(crate) fn prun_tables(&mut self) -> [&mut [u32]; 4] {
+		[&mut self.slice_twst_prun, &mut self.slice_flip_prun, &mut self.ccomb_eperm_prun, &mut self.mperm_cperm_prun]
+	}
+
+	/// Pruning tables, computed from the move tables
+	pub(crate) fn init_prun(&mut self, sctx: &StaticContext, progress: &mut dyn FnMut(Progress)) {
+		if self.profile == TableProfile::LowMemory {
+			// phase 2 first, so that its temporary move tables are freed before the phase 1 table is allocated
+			let mut sym_move = vec![0u16; N_PERM_SYM * N_MOVES_P2];
+			for i in 0..N_PERM_SYM {
+				for j in 0..N_MOVES_P2 {
+					sym_move[i * N_MOVES_P2 + j] = self.cperm_move_p2(sctx, i as u16, j);
+				}
+			}
+			self.mperm_cperm_prun = zeroed(N_MPERM * N_PERM_SYM / 8 + 1);
+			init_raw_sym_prun(&mut self.mperm_cperm_prun, &self.mperm_move, &self.mperm_conj, &sym_move, &self.eperm_selfsym, N_MPERM, N_PERM_SYM, 0x8ea34, "mperm_cperm_prun", progress);
+			for i in 0..N_PERM_SYM {
+				for j in 0..N_MOVES_P2 {
+					sym_move[i * N_MOVES_P2 + j] = self.eperm_move_p2(sctx, i as u16, j);
+				}
+			}
+			self.mperm_eperm_prun = zeroed(N_MPERM * N_PERM_SYM / 8 + 1);
+			init_raw_sym_prun(&mut self.mperm_eperm_prun, &self.mperm_move, &self.mperm_conj, &sym_move, &self.eperm_selfsym, N_MPERM, N_PERM_SYM, 0x8ea24, "mperm_eperm_prun", progress);
+			drop(sym_move);
+			self.slice_twst_prun = zeroed(N_SLICE * N_TWST_SYM / 8 + 1);
+			init_raw_sym_prun(&mut self.slice_twst_prun, &self.slice_move, &self.slice_conj, &self.twst_move, &self.twst_selfsym, N_SLICE, N_TWST_SYM, 0x69603, "slice_twst_prun", progress);
+			return;
+		}
+		self.alloc_prun();
+		init_raw_sym_prun(&mut self.slice_twst_prun, &self.slice_move, &self.slice_conj, &self.twst_move, &self.twst_selfsym, N_SLICE, N_TWST_SYM, 0x69603, "slice_twst_prun", progress);
+		init_raw_sym_prun(&mut self.slice_flip_prun, &self.slice_move, &self.slice_conj, &self.flip_move, &self.flip_selfsym, N_SLICE, N_FLIP_SYM, 0x69603, "slice_flip_prun", progress);
+		init_raw_sym_prun(&mut self.ccomb_eperm_prun, &self.ccomb_move, &self.ccomb_conj, &self.eperm_move, &self.eperm_selfsym, N_CCOMB, N_PERM_SYM, 0x7c824, "ccomb_eperm_prun", progress);
+		init_raw_sym_prun(&mut self.mperm_cperm_prun, &self.mperm_move, &self.mperm_conj, &self.cperm_move, &self.eperm_selfsym, N_MPERM, N_PERM_SYM, 0x8ea34, "mperm_cperm_prun", progress);
+		if self.profile == TableProfile::TwistFlip {
+			let (flip_move, flip_conj) = flip_raw_tables(sctx);
+			init_raw_sym_prun(&mut self.twst_flip_prun, &flip_move, &flip_conj, &self.twst_move, &self.twst_selfsym, N_FLIP, N_TWST_SYM, 0xc703, "twst_flip_prun", progress);
+		}
+	}
+
+	/// `eperm_raw2sym[raw]`, or the same computed from `eperm_rawsym` if the table is left out
+	pub(crate) fn eperm_raw2sym(&self, sctx: &StaticContext, raw: u16) -> u16 {
+		match self.eperm_raw2sym.get(raw as usize) {
+			Some(&sym) => sym,
+			None => {
+				// the representative is the conjugate of `raw` by the stored symmetry
+				let s = (self.eperm_rawsym[raw as usize >> 3] >> ((raw & 7) << 2) & 0xf) as usize;
+				let mut c = Cubie::new();
+				let mut d = Cubie::new();
+				let mut e = Cubie::new();
+				c.set_eperm(raw);
+				Cubie::edge_mult(&sctx.symcube[s], &c, &mut e);
+				Cubie::edge_mult(&e, &sctx.symcube[sctx.symmuli[0][s] as usize], &mut d);
+				let idx = self.eperm_sym2raw.binary_search(&(d.get_eperm() as u16)).unwrap();
+				(idx << 4 | s) as u16
+			}
+		}
+	}
+
+	/// `cperm_move[idx * N_MOVES_P2 + m]`, or the same computed from the cubie if the table is left out
+	pub(crate) fn cperm_move_p2(&self, sctx: &StaticContext, idx: u16, m: usize) -> u16 {
+		match self.cperm_move.get(idx as usize * N_MOVES_P2 + m) {
+			Some(&sym) => sym,
+			None => {
+				let mut c = Cubie::new();
+				let mut d = Cubie::new();
+				c.set_cperm(self.eperm_sym2raw[idx as usize]);
+				Cubie::corn_mult(&c, &sctx.movecube[P2MOVES[m] as usize], &mut d);
+				esym2csym(self.eperm_raw2sym(sctx, d.get_cperm() as u16))
+			}
+		}
+	}
+
+	/// `eperm_move[idx * N_MOVES_P2 + m]`, or the same computed from the cubie if the table is left out
+	pub(crate) fn eperm_move_p2(&self, sctx: &StaticContext, idx: u16, m: usize) -> u16 {
+		match self.eperm_move.get(idx as usize * N_MOVES_P2 + m) {
+			Some(&sym) => sym,
+			None => {
+				let mut c = Cubie::new();
+				let mut d = Cubie::new();
+				c.set_eperm(self.eperm_sym2raw[idx as usize]);
+				Cubie::edge_mult(&c, &sctx.movecube[P2MOVES[m] as usize], &mut d);
+				self.eperm_raw2sym(sctx, d.get_eperm() as u16)
+			}
+		}
+	}
+
+}
+
+/// Access to the tables of a [`TableProfile`] by the two-phase search
+///
+/// The search is generic over it, so that the profile is chosen once per solve and the
+/// node loops of each profile only look up the tables it has.
+pub(crate) trait ProfileTables {
+	/// The phase 2 moves are computed from cubies, and pruned by `mperm_eperm_prun` instead of `ccomb_eperm_prun`
+	const LOW_MEMORY: bool = false;
+
+	/// Phase 1 pruning value of the sym-coordinates twist and flip, and of the slice
+	fn phase1_prun(stbl: &StaticTables, twst: usize, tsym: usize, flip: usize, fsym: usize, slice: usize) -> u32;
+
+	fn eperm_raw2sym(_sctx: &StaticContext, stbl: &StaticTables, raw: u16) -> u16 {
+		stbl.eperm_raw2sym[raw as usize]
+	}
+
+	fn cperm_move(_sctx: &StaticContext, stbl: &StaticTables, idx: u16, m: usize) -> u16 {
+		stbl.cperm_move[idx as usize * N_MOVES_P2 + m]
+	}
+
+	fn eperm_move(_sctx: &StaticContext, stbl: &StaticTables, idx: u16, m: usize) -> u16 {
+		stbl.eperm_move[idx as usize * N_MOVES_P2 + m]
+	}
+}
+
+/// [`TableProfile::Standard`]
+pub(crate) struct StandardTables;
+
+/// [`TableProfile::LowMemory`]
+pub(crate) struct LowMemoryTables;
+
+/// [`TableProfile::TwistFlip`]
+pub(crate) struct TwistFlipTables;
+
+impl ProfileTables for StandardTables {
+	fn phase1_prun(stbl: &StaticTables, twst: usize, tsym: usize, flip: usize, fsym: usize, slice: usize) -> u32 {
+		core::cmp::max(
+			get_pruning(&stbl.slice_twst_prun, twst * N_SLICE + stbl.slice_conj[slice * 8 + tsym] as usize),
+			get_pruning(&stbl.slice_flip_prun, flip * N_SLICE + stbl.slice_conj[slice * 8 + fsym] as usize)
+		)
+	}
+}
+
+impl ProfileTables for LowMemoryTables {
+	const LOW_MEMORY: bool = true;
+
+	fn phase1_prun(stbl: &StaticTables, twst: usize, tsym: usize, flip: usize, _fsym: usize, slice: usize) -> u32 {
+		// no slice x flip table, an unsolved flip takes at least one move
+		get_pruning(&stbl.slice_twst_prun, twst * N_SLICE + stbl.slice_conj[slice * 8 + tsym] as usize).max((flip != 0) as u32)
+	}
+
+	fn eperm_raw2sym(sctx: &StaticContext, stbl: &StaticTables, raw: u16) -> u16 {
+		stbl.eperm_raw2sym(sctx, raw)
+	}
+
+	fn cperm_move(sctx: &StaticContext, stbl: &StaticTables, idx: u16, m: usize) -> u16 {
+		stbl.cperm_move_p2(sctx, idx, m)
+	}
+
+	fn eperm_move(sctx: &StaticContext, stbl: &StaticTables, idx: u16, m: usize) -> u16 {
+		stbl.eperm_move_p2(sctx, idx, m)
+	}
+}
+
+impl ProfileTables for TwistFlipTables {
+	fn phase1_prun(stbl: &StaticTables, twst: usize, tsym: usize, flip: usize, fsym: usize, slice: usize) -> u32 {
+		StandardTables::phase1_prun(stbl, twst, tsym, flip, fsym, slice)
+			.max(get_pruning(&stbl.twst_flip_prun, twst * N_FLIP + stbl.flip_s2rf[flip * 8 + (fsym ^ tsym)] as usize))
+	}
+}
+
+/// Moves and conjugations by the 8 symmetries of phase 1 of the raw flip
+pub(crate) fn flip_raw_tables(sctx: &StaticContext) -> (Vec<u16>, Vec<u16>) {
+	let mut flip_move = vec![0; N_FLIP * N_MOVES_P1];
+	let mut flip_conj = vec![0; N_FLIP * 8];
+	let mut c = Cubie::new();
+	let mut d = Cubie::new();
+	let mut e = Cubie::new();
+	for i in 0..N_FLIP {
+		c.set_flip(i as u16);
+		for j in 0..N_MOVES_P1 {
+			Cubie::edge_mult(&c, &sctx.movecube[j], &mut d);
+			flip_move[i * N_MOVES_P1 + j] = d.get_flip() as u16;
+		}
+		for j in 0..8 {
+			Cubie::edge_mult(&sctx.symcube[j << 1], &c, &mut e);
+			Cubie::edge_mult(&e, &sctx.symcube[j << 1], &mut d);
+			flip_conj[i * 8 + j] = d.get_flip() as u16;
+		}
+	}
+	(flip_move, flip_conj)
+}
+
+fn zeroed<T: Clone + Default>(len: usize) -> Box<[T]> {
+	vec![T::default(); len].into_boxed_slice()
+}
@@ -0,0 +1,377 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::{Arc, OnceLock};
+
+#[cfg(feature = "std")]
+use crate::optimal::OptimalTables;
+use crate::{Progress, StaticContext, StaticTables, TableProfile};
+
+const MAGIC: &[u8; 4] = b"M2PT";
+/// Magic of the tables of [`TableProfile::LowMemory`]
+const MAGIC_LOW_MEMORY: &[u8; 4] = b"M2PL";
+/// Magic of the tables of [`TableProfile::TwistFlip`]
+const MAGIC_TWIST_FLIP: &[u8; 4] = b"M2PF";
+/// Bump whenever the layout or the content of [`StaticTables`] changes
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+
+#[cfg(feature = "std")]
+static GLOBAL_TABLES: OnceLock<Arc<Tables>> = OnceLock::new();
+
+#[cfg(feature = "embedded-tables")]
+static EMBEDDED_PRUN: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/prun_tables.bin"));
+
+/// The move and pruning tables of the two-phase search
+///
+/// The tables are built implicitly the first time a cube is solved, which takes a noticeable
+/// time. Servers can build them at startup with [`init`](crate::init). Short-lived processes
+/// can instead enable the `embedded-tables` feature, or keep them in a file and install them
+/// before solving:
+///
+/// ```no_run
+/// use min2phase::Tables;
+///
+/// let _ = Tables::load_or_build("/tmp/min2phase.tables").install();
+/// ```
+///
+/// The file stores the tables in little endian, after a header with a version and a CRC-32
+/// checksum. The tables take about 570KB, so reading and verifying the whole file is
+/// already much faster than building them; memory mapping would need an unsafe,
+/// platform-dependent layout for no noticeable gain. See [`TableProfile`] for smaller or
+/// faster tables.
+///
+/// By default, all solvers share the same tables. A [`Solver`](crate::Solver) can also be
+/// given its own, see [`SolverBuilder::tables`](crate::SolverBuilder::tables). Without the
+/// `std` feature there are no shared tables, every solver must be given its own, e.g. built
+/// with [`Tables::build`] or read from bytes in flash with [`Tables::from_bytes`].
+pub struct Tables {
+	pub(crate) sctx: Box<StaticContext>,
+	pub(crate) stbl: Box<StaticTables>,
+	#[cfg(feature = "std")]
+	otbl: OnceLock<Box<OptimalTables>>,
+}
+
+impl Tables {
+	fn new(sctx: Box<StaticContext>, stbl: Box<StaticTables>) -> Self {
+		Tables {
+			sctx,
+			stbl,
+			#[cfg(feature = "std")]
+			otbl: OnceLock::new(),
+		}
+	}
+
+	/// Build the tables from scratch
+	pub fn build() -> Self {
+		Self::build_with_progress(|_| ())
+	}
+
+	/// Same as [`Tables::build`], reporting each completed table and each depth of the
+	/// breadth-first search of the pruning tables to `progress`
+	pub fn build_with_progress<F: FnMut(Progress)>(progress: F) -> Self {
+		Self::build_with_profile(TableProfile::Standard, progress)
+	}
+
+	/// Same as [`Tables::build_with_progress`], building the tables of `profile`
+	///
+	/// ```
+	/// use std::sync::Arc;
+	/// use min2phase::{Cube, Solver, TableProfile, Tables};
+	///
+	/// let tables = Tables::build_with_profile(TableProfile::LowMemory, |_| ());
+	/// let solver = Solver::builder().tables(Arc::new(tables)).build();
+	/// let cube = Cube::from_moves("R U R' U' F2").unwrap();
+	/// assert!(cube.apply(&solver.solve(&cube).unwrap()).is_solved());
+	/// ```
+	pub fn build_with_profile<F: FnMut(Progress)>(profile: TableProfile, mut progress: F) -> Self {
+		let sctx = StaticContext::box_new();
+		let mut stbl = StaticTables::box_zeroed(profile);
+		stbl.init_move(&sctx, &mut progress);
+		stbl.init_prun(&sctx, &mut progress);
+		Tables::new(sctx, stbl)
+	}
+
+	/// The profile the tables were built with
+	pub fn profile(&self) -> TableProfile {
+		self.stbl.profile
+	}
+
+	/// Tables with the pruning tables generated at build time, only the cheap symmetry and
+	/// move tables are built
+	///
+	/// This is how the tables in use are initialized with the `embedded-tables` feature.
+	#[cfg(feature = "embedded-tables")]
+	pub fn embedded() -> Self {
+		Self::embedded_with_progress(|_| ())
+	}
+
+	#[cfg(feature = "embedded-tables")]
+	fn embedded_with_progress<F: FnMut(Progress)>(mut progress: F) -> Self {
+		let sctx = StaticContext::box_new();
+		let mut stbl = StaticTables::box_zeroed(TableProfile::Standard);
+		stbl.init_move(&sctx, &mut progress);
+		stbl.alloc_prun();
+		let mut buf = EMBEDDED_PRUN;
+		for table in stbl.prun_tables() {
+			for v in table.iter_mut() {
+				let (bytes, rest) = buf.split_at(4);
+				*v = u32::from_le_bytes(bytes.try_into().unwrap());
+				buf = rest;
+			}
+		}
+		Tables::new(sctx, stbl)
+	}
+
+	/// Load tables saved by [`Tables::save`]
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the file is corrupt or was written by an
+	/// incompatible version.
+	#[cfg(feature = "std")]
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		Self::read_from(&mut fs::File::open(path)?)
+	}
+
+	/// Save the tables to be loaded with [`Tables::load`]
+	#[cfg(feature = "std")]
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let mut file = fs::File::create(path)?;
+		self.write_to(&mut file)?;
+		file.sync_all()
+	}
+
+	/// Load the tables from `path`, or build them if the file is missing or corrupt
+	///
+	/// Freshly built tables are saved to `path` for the next time, failing to save them is
+	/// not an error.
+	#[cfg(feature = "std")]
+	pub fn load_or_build<P: AsRef<Path>>(path: P) -> Self {
+		match Self::load(&path) {
+			Ok(tables) => tables,
+			Err(_) => {
+				let tables = Self::build();
+				let _ = tables.save(&path);
+				tables
+			}
+		}
+	}
+
+	#[cfg(feature = "std")]
+	pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+		let mut buf = Vec::new();
+		reader.read_to_end(&mut buf)?;
+		Self::parse(&buf).map_err(invalid_data)
+	}
+
+	#[cfg(feature = "std")]
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writer.write_all(&self.to_bytes())
+	}
+
+	/// Tables in the format of [`Tables::save`], e.g. a file embedded in flash memory
+	///
+	/// Return `None` if the bytes are corrupt or were written by an incompatible version.
+	pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		Self::parse(bytes).ok()
+	}
+
+	/// The bytes of the file written by [`Tables::save`]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut buf = Vec::with_capacity(HEADER_LEN + self.stbl.payload_len());
+		buf.extend_from_slice(match self.stbl.profile {
+			TableProfile::Standard => MAGIC,
+			TableProfile::LowMemory => MAGIC_LOW_MEMORY,
+			TableProfile::TwistFlip => MAGIC_TWIST_FLIP,
+		});
+		buf.extend_from_slice(&VERSION.to_le_bytes());
+		buf.extend_from_slice(&(self.stbl.payload_len() as u32).to_le_bytes());
+		buf.extend_from_slice(&[0; 4]);
+		self.stbl.write_payload(&mut buf);
+		let crc = crc32(&buf[HEADER_LEN..]);
+		buf[12..HEADER_LEN].copy_from_slice(&crc.to_le_bytes());
+		buf
+	}
+
+	fn parse(buf: &[u8]) -> Result<Self, &'static str> {
+		let profile = match buf.get(0..4) {
+			Some(magic) if magic == MAGIC => TableProfile::Standard,
+			Some(magic) if magic == MAGIC_LOW_MEMORY => TableProfile::LowMemory,
+			Some(magic) if magic == MAGIC_TWIST_FLIP => TableProfile::TwistFlip,
+			_ => return Err("not a min2phase table file"),
+		};
+		if buf.len() < HEADER_LEN {
+			return Err("truncated table file");
+		}
+		if u32_at(buf, 4) != VERSION {
+			return Err("unsupported table version");
+		}
+		let payload = &buf[HEADER_LEN..];
+		let mut stbl = StaticTables::box_zeroed(profile);
+		stbl.alloc_prun();
+		if u32_at(buf, 8) as usize != payload.len() || payload.len() != stbl.payload_len() {
+			return Err("truncated table file");
+		}
+		if u32_at(buf, 12) != crc32(payload) {
+			return Err("table checksum mismatch");
+		}
+		stbl.read_payload(payload);
+		Ok(Tables::new(StaticContext::box_new(), stbl))
+	}
+
+	/// Whether the tables in use are ready, i.e. were built or installed
+	#[cfg(feature = "std")]
+	pub fn is_warm() -> bool {
+		GLOBAL_TABLES.get().is_some()
+	}
+
+	/// Tables of the optimal solver, built on first use
+	#[cfg(feature = "std")]
+	pub(crate) fn optimal(&self) -> &OptimalTables {
+		self.otbl.get_or_init(|| OptimalTables::box_new(&self.sctx, &self.stbl))
+	}
+
+	/// Use these tables for all subsequent solves
+	///
+	/// Fails, returning the tables, if the tables in use were already built or installed.
+	/// Solvers given their own tables are not affected.
+	#[cfg(feature = "std")]
+	pub fn install(self) -> Result<(), Tables> {
+		let mut tables = Some(self);
+		GLOBAL_TABLES.get_or_init(|| Arc::new(tables.take().unwrap()));
+		match tables {
+			None => Ok(()),
+			Some(tables) => Err(tables),
+		}
+	}
+}
+
+impl fmt::Debug for Tables {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Tables").finish_non_exhaustive()
+	}
+}
+
+/// The tables shared by default
+#[cfg(feature = "std")]
+pub(crate) fn global_tables() -> &'static Arc<Tables> {
+	GLOBAL_TABLES.get_or_init(|| Arc::new(build_global(&mut |_| ())))
+}
+
+/// Build the tables in use if needed, return whether they were already warm
+#[cfg(feature = "std")]
+pub(crate) fn init_global(progress: &mut dyn FnMut(Progress)) -> bool {
+	let mut warm = true;
+	GLOBAL_TABLES.get_or_init(|| {
+		warm = false;
+		Arc::new(build_global(progress))
+	});
+	warm
+}
+
+#[cfg(feature = "std")]
+fn build_global(progress: &mut dyn FnMut(Progress)) -> Tables {
+	#[cfg(not(feature = "embedded-tables"))]
+	return Tables::build_with_progress(progress);
+	#[cfg(feature = "embedded-tables")]
+	return Tables::embedded_with_progress(progress);
+}
+
+#[cfg(feature = "std")]
+fn invalid_data(msg: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+/// Serialize the fields of [`StaticTables`] in the given order, in little endian
+macro_rules! table_fields {
+	($($name:ident: $ty:ty),* $(,)?) => {
+		impl StaticTables {
+			fn payload_len(&self) -> usize {
+				0 $(+ self.$name.len() * core::mem::size_of::<$ty>())*
+			}
+
+			fn write_payload(&self, buf: &mut Vec<u8>) {
+				$(for v in self.$name.iter() {
+					buf.extend_from_slice(&v.to_le_bytes());
+				})*
+			}
+
+			fn read_payload(&mut self, mut buf: &[u8]) {
+				$(for v in self.$name.iter_mut() {
+					let (bytes, rest) = buf.split_at(core::mem::size_of::<$ty>());
+					*v = <$ty>::from_le_bytes(bytes.try_into().unwrap());
+					buf = rest;
+				})*
+			}
+		}
+	};
+}
+
+table_fields! {
+	perm_sym_inv: u16,
+	cperm2comb: u8,
+	flip_sym2raw: u16,
+	flip_raw2sym: u16,
+	flip_selfsym: u16,
+	twst_sym2raw: u16,
+	twst_raw2sym: u16,
+	twst_selfsym: u16,
+	eperm_sym2raw: u16,
+	eperm_raw2sym: u16,
+	eperm_rawsym: u32,
+	eperm_selfsym: u16,
+	flip_move: u16,
+	twst_move: u16,
+	slice_move: u16,
+	slice_conj: u16,
+	cperm_move: u16,
+	eperm_move: u16,
+	mperm_move: u16,
+	mperm_conj: u16,
+	ccomb_move: u16,
+	ccomb_conj: u16,
+	slice_flip_prun: u32,
+	slice_twst_prun: u32,
+	ccomb_eperm_prun: u32,
+	mperm_cperm_prun: u32,
+	mperm_eperm_prun: u32,
+	flip_s2rf: u16,
+	twst_flip_prun: u32,
+}
+
+static CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+	let mut table = [0u32; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut j = 0;
+		while j < 8 {
+			crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+			j += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+	table
+}
+
+/// CRC-32 as used by zip and png
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for &b in data {
+		crc = CRC_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+	}
+	!crc
+}
@@ -0,0 +1,46 @@
+use min2phase::{try_solve, Algorithm, Cube, Face, Move};
+
+#[test]
+fn algorithm_parse_display() {
+	let alg: Algorithm = "R U2 F' D1 L3 B-".parse().unwrap();
+	assert_eq!(alg.to_string(), "R U2 F' D L' B'");
+	assert_eq!(alg.len(), 6);
+	assert_eq!(alg.len_qtm(), 7);
+	assert_eq!(alg.moves()[1], Move::new(Face::U, 2));
+	assert_eq!(alg.moves()[2].face(), Face::F);
+	assert_eq!(alg.moves()[2].power(), 3);
+	assert_eq!("".parse::<Algorithm>().unwrap(), Algorithm::new());
+	assert_eq!("F2".parse::<Move>().unwrap(), Move::new(Face::F, 2));
+
+	let err = "R U X".parse::<Algorithm>().unwrap_err();
+	assert_eq!((err.position(), err.found()), (4, Some('X')));
+	let err = "' R".parse::<Algorithm>().unwrap_err();
+	assert_eq!(err.position(), 0);
+	let err = "R U".parse::<Move>().unwrap_err();
+	assert_eq!((err.position(), err.found()), (2, Some('U')));
+	assert_eq!("".parse::<Move>().unwrap_err().found(), None);
+}
+
+#[test]
+fn algorithm_cancellation() {
+	let alg = |s: &str| s.parse::<Algorithm>().unwrap();
+	assert_eq!(&alg("R U") + &alg("U R'"), alg("R U2 R'"));
+	assert_eq!(&alg("R U") + &alg("U' R'"), Algorithm::new());
+	assert_eq!(&alg("R L") + &alg("R'"), alg("L"));
+	assert_eq!(&alg("R L") + &alg("R"), alg("R2 L"));
+	assert_eq!(&alg("U D") + &alg("R"), alg("U D R"));
+	assert_eq!(alg("R U F'").inverse(), alg("F U' R'"));
+	let mut raw = Algorithm::new();
+	raw.push(Move::new(Face::R, 1));
+	raw.push(Move::new(Face::R, 3));
+	assert_eq!(raw.len(), 2);
+	assert!(Cube::solved().apply(&raw).is_solved());
+}
+
+#[test]
+fn algorithm_solution() {
+	let cube = Cube::from_moves("R U R' U' F2 D' L B2").unwrap();
+	let solution = try_solve(&cube.to_facelet(), 21).unwrap();
+	assert!(cube.apply(&solution).is_solved());
+	assert_eq!(Cube::solved().apply(&solution.inverse()), cube);
+}
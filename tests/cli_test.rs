@@ -0,0 +1,76 @@
+use min2phase::{Algorithm, Cube};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_min2phase"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+	child.wait_with_output().unwrap()
+}
+
+/// The JSON lines of the output, skipping the diagnostics of debug builds
+fn json_lines(output: &Output) -> Vec<String> {
+	String::from_utf8_lossy(&output.stdout).lines().filter(|line| line.starts_with('{')).map(String::from).collect()
+}
+
+#[test]
+fn cli_apply_verify() {
+	let output = run(&["apply", "R U R'", "U'"], "");
+	assert!(output.status.success());
+	let facelet = String::from_utf8(output.stdout).unwrap();
+	assert_eq!(facelet.trim(), Cube::from_moves("R U R' U'").unwrap().to_facelet());
+
+	let output = run(&["verify", "--json"], &format!("{}\nUUU\n", facelet.trim()));
+	assert_eq!(output.status.code(), Some(1));
+	let lines = json_lines(&output);
+	assert_eq!(lines.len(), 2);
+	assert!(lines[0].contains("\"valid\":true"));
+	assert!(lines[1].contains("\"valid\":false") && lines[1].contains("\"code\":1"));
+
+	assert_eq!(run(&["apply", "R X"], "").status.code(), Some(2));
+	assert_eq!(run(&["solve", "--max-length"], "").status.code(), Some(2));
+	assert_eq!(run(&["frobnicate"], "").status.code(), Some(2));
+
+	let output = run(&[], "");
+	assert_eq!(output.status.code(), Some(2));
+	assert!(output.stdout.is_empty());
+	for args in [&["--help"][..], &["help"], &["solve", "--help"], &["bench", "-h"]] {
+		let output = run(args, "");
+		assert!(output.status.success());
+		assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage: min2phase"));
+	}
+}
+
+#[test]
+fn cli_solve_scramble() {
+	let output = run(&["scramble", "--json", "--count", "3"], "");
+	assert!(output.status.success());
+	let scrambles = json_lines(&output);
+	assert_eq!(scrambles.len(), 3);
+	let facelets: Vec<String> = scrambles.iter().map(|line| line.split('"').nth(7).unwrap().to_string()).collect();
+
+	let output = run(&["solve", "--json", "--max-length", "20"], &facelets.join("\n"));
+	assert!(output.status.success());
+	let solutions = json_lines(&output);
+	assert_eq!(solutions.len(), 3);
+	for (facelet, line) in facelets.iter().zip(&solutions) {
+		let solution: Algorithm = line.split('"').nth(7).unwrap().parse().unwrap();
+		assert!(solution.len() <= 20 && Cube::from_facelet(facelet).unwrap().apply(&solution).is_solved());
+	}
+
+	let seeded = run(&["scramble", "--seed", "5", "--count", "2"], "");
+	assert_eq!(seeded.stdout, run(&["scramble", "--seed", "5", "--count", "2"], "").stdout);
+	let output = run(&["scramble", "--moves", "15", "--json"], "");
+	assert!(json_lines(&output)[0].starts_with("{\"scramble\":"));
+	let output = run(&["bench", "--count", "3", "--json"], "");
+	assert!(output.status.success() && json_lines(&output)[0].contains("\"cubes\":3"));
+	assert!(json_lines(&output)[0].contains("\"avg_probes\":"));
+	let output = run(&["bench", "--count", "3", "--seed", "1", "--profile", "twist-flip", "--json"], "");
+	assert!(output.status.success() && json_lines(&output)[0].starts_with("{\"profile\":\"twist-flip\",\"cubes\":3,\"failed\":0,"));
+	assert_eq!(run(&["bench", "--profile", "huge"], "").status.code(), Some(2));
+}
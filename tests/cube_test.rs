@@ -0,0 +1,54 @@
+use min2phase::{Algorithm, Cube};
+
+const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+#[test]
+fn cube_facelet_roundtrip() {
+	assert!(SOLVED.parse::<Cube>().unwrap().is_solved());
+	assert_eq!(Cube::solved().to_facelet(), SOLVED);
+	for _ in 0..100 {
+		let cube = Cube::random();
+		assert_eq!(Cube::from_facelet(&cube.to_facelet()), Ok(cube));
+	}
+	assert!(Cube::from_facelet("UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBB").is_err());
+}
+
+#[test]
+fn cube_compose_inverse() {
+	let a = Cube::from_moves("R U R' U'").unwrap();
+	let b = Cube::from_moves("F2 D L'").unwrap();
+	assert_eq!(a.compose(&b), Cube::from_moves("R U R' U' F2 D L'").unwrap());
+	assert_eq!(a.apply(&"F2 D L'".parse().unwrap()), a.compose(&b));
+	assert_eq!(a.inverse(), Cube::from_moves("U R U' R'").unwrap());
+	assert!(a.compose(&a.inverse()).is_solved());
+	assert!(!a.is_solved());
+	let alg: Algorithm = "R U R' U'".parse().unwrap();
+	assert!(Cube::solved().apply(&alg).apply(&alg.inverse()).is_solved());
+	assert!(Cube::from_moves("R X").is_err());
+}
+
+#[test]
+fn cube_solve() {
+	let cube = Cube::random();
+	let solution = cube.solve(21).unwrap();
+	assert!(solution.len() <= 21);
+	assert!(cube.apply(&solution).is_solved(), "solution={solution}");
+}
+
+#[test]
+fn cube_seeded_random() {
+	// pinned, the same seed must give the same cube and scramble on all platforms and versions
+	assert_eq!(min2phase::random_cube_with(&mut min2phase::seeded_rng(2024)), "DUUBURUUFLFFBRDLUDBBUFFRDDBLBDLDRBFBFLRDLDLUFRFRRBLRLU");
+	assert_eq!(min2phase::random_moves_with(&mut min2phase::seeded_rng(2024), 10), "L2 R  B' L  F2 L  R  B  D  L  ");
+
+	let mut rng = min2phase::seeded_rng(7);
+	let cubes: Vec<Cube> = (0..10).map(|_| Cube::random_with(&mut rng)).collect();
+	let mut rng = min2phase::seeded_rng(7);
+	assert!(cubes.iter().all(|cube| *cube == Cube::random_with(&mut rng)));
+	assert_ne!(cubes[0], cubes[1]);
+	assert_ne!(Cube::random_with(&mut min2phase::seeded_rng(8)), cubes[0]);
+
+	let rng: &mut dyn rand::RngCore = &mut rand::thread_rng();
+	let scramble: Algorithm = min2phase::random_moves_with(rng, 25).parse().unwrap();
+	assert_eq!(scramble.len(), 25);
+}
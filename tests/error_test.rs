@@ -0,0 +1,49 @@
+use min2phase::{solve, try_solve, Cube, SolveError};
+
+const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+fn swap(facelet: &str, i: usize, j: usize) -> String {
+	let mut f = facelet.as_bytes().to_vec();
+	f.swap(i, j);
+	String::from_utf8(f).unwrap()
+}
+
+#[test]
+fn solve_error_variants() {
+	// U6 R2 is the UR edge, U9 R1 F3 is the URF corner
+	assert_eq!(try_solve(&SOLVED[1..], 21), Err(SolveError::InvalidFacelet));
+	assert_eq!(try_solve(&SOLVED.replace("UUUUUUUUU", "UUUUUUUUX"), 21), Err(SolveError::InvalidFacelet));
+	assert_eq!(try_solve(&swap(SOLVED, 5, 10), 21), Err(SolveError::FlippedEdge));
+	assert_eq!(try_solve(&swap(SOLVED, 10, 28), 21), Err(SolveError::MissingEdge));
+	assert_eq!(try_solve(&swap(SOLVED, 8, 9), 21), Err(SolveError::MissingCorner));
+	let twisted = Cube::solved().to_facelet();
+	let twisted = swap(&swap(&twisted, 8, 9), 8, 20);
+	assert_eq!(try_solve(&twisted, 21), Err(SolveError::TwistedCorner));
+	let parity = swap(&swap(SOLVED, 5, 7), 10, 19);
+	assert_eq!(try_solve(&parity, 21), Err(SolveError::Parity));
+
+	let cube = Cube::from_moves("R U R' U' F2 B2 D L").unwrap();
+	assert_eq!(cube.solve(3), Err(SolveError::NoSolution));
+}
+
+#[test]
+fn solve_short_limit() {
+	// fails at once rather than searching phase 1 beyond the length limit
+	let cube = Cube::from_moves("R U F").unwrap();
+	for maxl in 0..3 {
+		assert_eq!(cube.solve(maxl), Err(SolveError::NoSolution));
+	}
+	assert_eq!(cube.solve(3).unwrap().len(), 3);
+}
+
+#[test]
+fn solve_error_codes() {
+	assert_eq!(solve(&SOLVED[1..], 21), "Error 1");
+	assert_eq!(solve(&swap(SOLVED, 5, 10), 21), "Error 3");
+	for code in 0..10 {
+		if let Some(err) = SolveError::from_code(code) {
+			assert_eq!(err.code(), code);
+			assert!(!err.to_string().is_empty());
+		}
+	}
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "log")]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use log::kv::{Key, Value, VisitSource};
+use log::{Log, Metadata, Record};
+use min2phase::{Cube, Solver, TableProfile, Tables};
+
+/// Keeps the key-values of every record
+struct Recorder(Mutex<Vec<HashMap<String, String>>>);
+
+struct KeyValues(HashMap<String, String>);
+
+impl VisitSource<'_> for KeyValues {
+	fn visit_pair(&mut self, key: Key<'_>, value: Value<'_>) -> Result<(), log::kv::Error> {
+		self.0.insert(key.to_string(), value.to_string());
+		Ok(())
+	}
+}
+
+impl Log for Recorder {
+	fn enabled(&self, _: &Metadata) -> bool {
+		true
+	}
+
+	fn log(&self, record: &Record) {
+		if record.target().starts_with("min2phase") {
+			let mut kvs = KeyValues(HashMap::new());
+			record.key_values().visit(&mut kvs).unwrap();
+			self.0.lock().unwrap().push(kvs.0);
+		}
+	}
+
+	fn flush(&self) {}
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+#[test]
+fn log_records() {
+	log::set_logger(&RECORDER).unwrap();
+	log::set_max_level(log::LevelFilter::Debug);
+
+	let tables = Tables::build_with_profile(TableProfile::LowMemory, |_| ());
+	let cube = Cube::from_moves("R U R' U' F2 D").unwrap();
+	let (solution, stats) = Solver::builder().tables(Arc::new(tables)).build().solve_with_stats(&cube);
+	assert!(cube.apply(&solution.unwrap()).is_solved());
+
+	let records = RECORDER.0.lock().unwrap();
+	assert!(records.iter().any(|kvs| kvs.get("table").is_some_and(|table| table == "eperm_sym2raw")
+		&& kvs.get("count").is_some_and(|count| count == "2768")));
+	let depths = records.iter().filter(|kvs| kvs.get("table").is_some_and(|table| table == "slice_twst_prun")).count();
+	assert!(depths > 1);
+	assert!(records.iter().any(|kvs| kvs.get("table").is_some_and(|table| table == "mperm_cperm_prun")
+		&& kvs.contains_key("depth") && kvs.contains_key("entries")));
+	let solve = records.iter().find(|kvs| kvs.contains_key("probes")).unwrap();
+	assert_eq!(solve["probes"], stats.probes.to_string());
+	assert_eq!(solve["urf_idx"], stats.urf_idx.to_string());
+}
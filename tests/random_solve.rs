@@ -1,3 +1,5 @@
+#![allow(clippy::assertions_on_constants)]
+
 use min2phase::{random_cube, random_moves, from_moves, apply_moves, solve};
 use std::time::Instant;
 
@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+
+use min2phase::{Algorithm, Cube, Face, Move, SolveError, Solver};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Record {
+	#[serde(with = "min2phase::serialize::cubie")]
+	cube: Cube,
+	solution: Result<Algorithm, SolveError>,
+}
+
+#[test]
+fn serde_strings() {
+	let cube = Cube::from_moves("R U R' U'").unwrap();
+	let json = serde_json::to_string(&cube).unwrap();
+	assert_eq!(json, format!("\"{}\"", cube.to_facelet()));
+	assert_eq!(serde_json::from_str::<Cube>(&json).unwrap(), cube);
+	assert!(serde_json::from_str::<Cube>("\"UUU\"").is_err());
+
+	let alg: Algorithm = "R U2 F'".parse().unwrap();
+	assert_eq!(serde_json::to_string(&alg).unwrap(), "\"R U2 F'\"");
+	assert_eq!(serde_json::from_str::<Algorithm>("\"R U2 F'\"").unwrap(), alg);
+	assert_eq!(serde_json::to_string(&Move::new(Face::B, 3)).unwrap(), "\"B'\"");
+	assert_eq!(serde_json::from_str::<Move>("\"D2\"").unwrap(), Move::new(Face::D, 2));
+	assert!(serde_json::from_str::<Move>("\"R U\"").is_err());
+	assert_eq!(serde_json::to_string(&Face::L).unwrap(), "\"L\"");
+	assert_eq!(serde_json::to_string(&SolveError::Timeout).unwrap(), "\"Timeout\"");
+
+	let solver = Solver::builder().max_length(20).build();
+	let improvement = solver.solutions(&cube).next().unwrap();
+	let json = serde_json::to_string(&improvement).unwrap();
+	assert!(json.starts_with(&format!("{{\"solution\":\"{}\",\"length\":", improvement.solution)), "json={json}");
+}
+
+#[test]
+fn serde_cubie() {
+	let cube = Cube::from_moves("F").unwrap();
+	let record = Record { cube, solution: cube.solve(21) };
+	let json = serde_json::to_string(&record).unwrap();
+	assert!(json.starts_with("{\"cube\":{\"ca\":[9,21,2,3,16,12,6,7],\"ea\":["), "json={json}");
+	assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+	let solved = "{\"cube\":{\"ca\":[0,1,2,3,4,5,6,7],\"ea\":[0,2,4,6,8,10,12,14,16,18,20,22]},\"solution\":{\"Err\":\"NoSolution\"}}";
+	let record: Record = serde_json::from_str(solved).unwrap();
+	assert!(record.cube.is_solved() && record.solution == Err(SolveError::NoSolution));
+	let flipped = solved.replace("[0,2,4", "[1,2,4");
+	let err = serde_json::from_str::<Record>(&flipped).unwrap_err();
+	assert!(err.to_string().contains(&SolveError::FlippedEdge.to_string()), "err={err}");
+	assert!(serde_json::from_str::<Record>(&solved.replace("[0,1,2", "[24,1,2")).is_err());
+}
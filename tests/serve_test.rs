@@ -0,0 +1,43 @@
+use min2phase::{Algorithm, Cube, Solver, PROTOCOL_VERSION};
+use std::time::Duration;
+
+fn serve(solver: &Solver, input: &str) -> Vec<String> {
+	let mut output = Vec::new();
+	min2phase::serve(solver, input.as_bytes(), &mut output).unwrap();
+	String::from_utf8(output).unwrap().lines().map(String::from).collect()
+}
+
+fn solution(line: &str) -> Algorithm {
+	line.split("\"solution\":\"").nth(1).unwrap().split('"').next().unwrap().parse().unwrap()
+}
+
+#[test]
+fn serve_protocol() {
+	let cube = Cube::from_moves("R U R' U' F2 D' L B2 U2 R'").unwrap();
+	let input = format!("{}\n\n# comment\n{} max=12 id=a1\nUUU id=x\n{} max=5\n{} max=x id=bad\n",
+		cube, cube, cube, cube);
+	let lines = serve(&Solver::builder().build(), &input);
+	assert_eq!(lines.len(), 6);
+	assert_eq!(lines[0], format!("{{\"protocol\":{},\"ready\":true}}", PROTOCOL_VERSION));
+
+	for line in &lines[1..3] {
+		assert!(line.contains("\"code\":0") && line.contains("\"probes\":"), "line={line}");
+		let solution = solution(line);
+		assert!(line.contains(&format!("\"length\":{},", solution.len())));
+		assert!(cube.apply(&solution).is_solved());
+	}
+	assert!(lines[1].starts_with("{\"id\":null,"));
+	assert!(lines[2].starts_with("{\"id\":\"a1\",") && solution(&lines[2]).len() <= 12);
+	assert!(lines[3].starts_with("{\"id\":\"x\",\"code\":1,\"error\":"));
+	assert!(lines[4].starts_with("{\"id\":null,\"code\":8,\"error\":"));
+	assert_eq!(lines[5], "{\"id\":\"bad\",\"code\":-1,\"error\":\"invalid field max=x\"}");
+}
+
+#[test]
+fn serve_timeout() {
+	let cube = Cube::random();
+	let solver = Solver::builder().max_length(16).time_limit(Duration::from_secs(3600)).build();
+	let lines = serve(&solver, &format!("{} timeout=50 id=7\n", cube));
+	assert_eq!(lines.len(), 2);
+	assert!(lines[1].starts_with("{\"id\":\"7\",\"code\":10,"), "line={}", lines[1]);
+}
@@ -0,0 +1,263 @@
+use min2phase::{Algorithm, BatchOptions, CancelToken, Cube, SolveError, Solver, Tables};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn solver_output_format() {
+	let cube = Cube::from_moves("R U R' U' F2 D' L B2 U2 R'").unwrap();
+	let facelet = cube.to_facelet();
+
+	let plain = Solver::builder().build().solve_facelet(&facelet).unwrap();
+	assert!(!plain.contains('.') && !plain.contains('('));
+
+	let verbose = Solver::builder().use_separator(true).append_length(true).build();
+	let solution = verbose.solve_facelet(&facelet).unwrap();
+	assert!(solution.contains(".  "), "solution={solution}");
+	let alg: Algorithm = solution.split(['.', '(']).collect::<Vec<_>>()[..2].concat().parse().unwrap();
+	assert!(solution.ends_with(&format!("({}f)", alg.len())), "solution={solution}");
+	assert!(cube.apply(&alg).is_solved());
+
+	let inverse = Solver::builder().inverse_solution(true).build();
+	let generator = inverse.solve(&cube).unwrap();
+	assert_eq!(Cube::solved().apply(&generator), cube);
+}
+
+#[test]
+fn solver_search_limits() {
+	for _ in 0..5 {
+		let cube = Cube::random();
+		let short = Solver::builder().max_length(21).min_probes(50).build().solve(&cube).unwrap();
+		assert!(short.len() <= 21 && cube.apply(&short).is_solved());
+
+		let no_premv = Solver::builder().max_pre_moves(0).build().solve(&cube).unwrap();
+		assert!(no_premv.len() <= 21 && cube.apply(&no_premv).is_solved());
+
+		let premv = Solver::builder().min_phase1_length_pre_moves(9).build().solve(&cube).unwrap();
+		assert!(premv.len() <= 21 && cube.apply(&premv).is_solved());
+	}
+
+	let cube = Cube::random();
+	assert_eq!(Solver::builder().max_length(16).max_probes(1).build().solve(&cube), Err(SolveError::ProbeLimit));
+	assert_eq!(Solver::builder().max_length(5).build().solve(&cube), Err(SolveError::NoSolution));
+}
+
+#[test]
+fn solver_time_limit() {
+	let limit = Duration::from_millis(100);
+	Solver::builder().build().solve(&Cube::random()).unwrap();
+	for _ in 0..5 {
+		let cube = Cube::random();
+		let now = Instant::now();
+		match Solver::builder().max_length(17).time_limit(limit).build().solve(&cube) {
+			Ok(solution) => assert!(solution.len() <= 17 && cube.apply(&solution).is_solved()),
+			Err(err) => assert_eq!(err, SolveError::Timeout),
+		}
+		assert!(now.elapsed() < limit * 3, "elapsed={:?}", now.elapsed());
+	}
+
+	let cube = Cube::random();
+	let solver = Solver::builder().min_probes(u64::MAX).time_limit(limit).build();
+	let solution = solver.solve(&cube).unwrap();
+	assert!(solution.len() <= 21 && cube.apply(&solution).is_solved());
+}
+
+#[test]
+fn solver_cancel() {
+	let solver = Solver::builder().max_length(16).build();
+	let cube = Cube::random();
+	let cancel = CancelToken::new();
+	let handle = {
+		let cancel = cancel.clone();
+		std::thread::spawn(move || {
+			std::thread::sleep(Duration::from_millis(50));
+			cancel.cancel();
+		})
+	};
+	let now = Instant::now();
+	assert_eq!(solver.solve_cancellable(&cube, &cancel), Err(SolveError::Cancelled));
+	assert!(now.elapsed() < Duration::from_secs(1), "elapsed={:?}", now.elapsed());
+	handle.join().unwrap();
+
+	let solver = Solver::builder().build();
+	assert_eq!(solver.solve_cancellable(&cube, &cancel), Err(SolveError::Cancelled));
+	let solution = solver.solve_cancellable(&cube, &CancelToken::new()).unwrap();
+	assert!(cube.apply(&solution).is_solved());
+}
+
+#[test]
+fn solver_solutions() {
+	for _ in 0..5 {
+		let cube = Cube::random();
+		let solver = Solver::builder().max_length(24).time_limit(Duration::from_secs(1)).build();
+		let mut last = None;
+		for improvement in solver.solutions(&cube).take(3) {
+			assert!(cube.apply(&improvement.solution).is_solved());
+			assert_eq!(improvement.solution.len(), improvement.length);
+			if let Some((length, probes)) = last {
+				assert!(improvement.length < length && improvement.probes >= probes);
+			}
+			last = Some((improvement.length, improvement.probes));
+		}
+		assert!(last.is_some());
+	}
+
+	let mut solutions = Solver::builder().max_length(16).build().solutions(&Cube::random());
+	solutions.cancel_token().cancel();
+	assert!(solutions.next().is_none());
+}
+
+#[test]
+fn solver_enumerate() {
+	let cube = Cube::from_moves("R U F D2 L' B2 U R2 F' D").unwrap();
+	let solver = Solver::builder().max_length(20).time_limit(Duration::from_secs(5)).build();
+	let all: Vec<_> = solver.enumerate(&cube).take(50).collect();
+	assert_eq!(all.len(), 50);
+	let distinct: std::collections::HashSet<_> = all.iter().map(|s| s.solution.clone()).collect();
+	assert_eq!(distinct.len(), all.len());
+	for s in &all {
+		assert!(s.solution.len() <= 20 && s.urf_idx < 6);
+		assert!(cube.apply(&s.solution).is_solved(), "solution={}", s.solution);
+	}
+
+	// few solutions exist that close to the optimum, the iteration ends once every probe is searched
+	let cube = Cube::from_moves("R U F D2 L'").unwrap();
+	let all: Vec<Algorithm> = Solver::builder().max_length(6).build().enumerate(&cube).map(|s| s.solution).collect();
+	assert!(all.contains(&"L D2 F' U' R'".parse().unwrap()));
+	assert!(all.iter().all(|solution| solution.len() <= 6 && cube.apply(solution).is_solved()));
+
+	// both sequences follow the same empty phase 1, each phase 2 solution is reported
+	let cube = Cube::from_moves("R2 U2 R2").unwrap();
+	let all: Vec<Algorithm> = Solver::builder().max_length(7).build().enumerate(&cube).map(|s| s.solution).collect();
+	assert!(all.contains(&"U D' F2 U2 F2 U' D".parse().unwrap()));
+	assert!(all.contains(&"D2 R2 L2 U2 R2 D2 L2".parse().unwrap()));
+}
+
+#[test]
+#[ignore = "builds the ~70MB optimal tables, run with cargo test --release -- --ignored"]
+fn solver_optimal() {
+	let solver = Solver::builder().build();
+	assert_eq!(solver.solve_optimal(&Cube::solved()), Ok(Algorithm::new()));
+	// T-permutation, 14 moves as usually executed, 11 moves optimally
+	let cube = Cube::from_moves("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap();
+	assert_eq!(solver.solve_optimal(&cube).unwrap().len(), 11);
+	for _ in 0..20 {
+		let scramble: Algorithm = min2phase::random_moves(9).parse().unwrap();
+		let cube = Cube::solved().apply(&scramble);
+		let solution = solver.solve_optimal(&cube).unwrap();
+		assert!(cube.apply(&solution).is_solved(), "solution={solution}");
+		assert!(solution.len() <= scramble.len() && solution.len() <= cube.solve(21).unwrap().len());
+		if !solution.is_empty() {
+			let shorter = Solver::builder().max_length(solution.len() as u8 - 1).build();
+			assert_eq!(shorter.solve_optimal(&cube), Err(SolveError::NoSolution));
+		}
+	}
+
+	let solver = Solver::builder().time_limit(Duration::from_millis(100)).build();
+	let superflip = Cube::from_moves("U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2").unwrap();
+	assert_eq!(solver.solve_optimal(&superflip), Err(SolveError::Timeout));
+}
+
+#[test]
+fn solver_own_tables() {
+	let tables = Arc::new(Tables::build());
+	let short = Solver::builder().tables(tables.clone()).max_length(20).min_probes(50).build();
+	let long = Solver::builder().tables(tables.clone()).max_length(24).use_separator(true).build();
+	let isolated = Solver::builder().tables(Arc::new(Tables::build())).build();
+	for _ in 0..10 {
+		let cube = Cube::random();
+		let solution = short.solve(&cube).unwrap();
+		assert!(solution.len() <= 20 && cube.apply(&solution).is_solved());
+		assert!(long.solve_facelet(&cube.to_facelet()).unwrap().contains('.'));
+		assert!(cube.apply(&isolated.solve(&cube).unwrap()).is_solved());
+	}
+
+	// the search state kept by the solver must not leak from one kind of search to another
+	let cube = Cube::from_moves("R U F D2 L'").unwrap();
+	assert_eq!(short.enumerate(&cube).take(3).count(), 3);
+	assert_eq!(short.solve_optimal(&cube).unwrap().len(), 5);
+	let cancel = CancelToken::new();
+	cancel.cancel();
+	assert_eq!(short.solve_cancellable(&cube, &cancel), Err(SolveError::Cancelled));
+	let solution = short.solve(&cube).unwrap();
+	assert!(solution.len() <= 20 && cube.apply(&solution).is_solved());
+	assert_eq!(short.clone().solve(&cube).unwrap().len(), solution.len());
+}
+
+#[test]
+fn solver_threads() {
+	let parallel = Solver::builder().threads(6).build();
+	for _ in 0..10 {
+		let cube = Cube::random();
+		let solution = parallel.solve(&cube).unwrap();
+		assert!(solution.len() <= 21 && cube.apply(&solution).is_solved());
+	}
+
+	let cube = Cube::random();
+	let solver = Solver::builder().threads(4).max_length(20).min_probes(200).build();
+	let solution = solver.solve(&cube).unwrap();
+	assert!(solution.len() <= 20 && cube.apply(&solution).is_solved());
+	let solver = Solver::builder().threads(3).max_length(16).max_probes(10).build();
+	assert_eq!(solver.solve(&cube), Err(SolveError::ProbeLimit));
+	let solver = Solver::builder().threads(3).max_length(5).build();
+	assert_eq!(solver.solve(&cube), Err(SolveError::NoSolution));
+
+	let limit = Duration::from_millis(100);
+	let solver = Solver::builder().threads(6).max_length(16).time_limit(limit).build();
+	let now = Instant::now();
+	assert_eq!(solver.solve(&cube), Err(SolveError::Timeout));
+	assert!(now.elapsed() < limit * 3, "elapsed={:?}", now.elapsed());
+	let cancel = CancelToken::new();
+	cancel.cancel();
+	assert_eq!(parallel.solve_cancellable(&cube, &cancel), Err(SolveError::Cancelled));
+}
+
+#[test]
+fn solver_batch() {
+	let mut facelets: Vec<String> = (0..20).map(|_| Cube::random().to_facelet()).collect();
+	facelets.push(Cube::solved().to_facelet());
+	facelets.push("UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBB".to_string());
+	let solver = Solver::builder().max_length(21).build();
+	let results = solver.solve_batch(&facelets, &BatchOptions::new());
+	assert_eq!(results.len(), facelets.len());
+	for (facelet, ret) in facelets.iter().zip(&results).take(21) {
+		let cube = Cube::from_facelet(facelet).unwrap();
+		let solution: Algorithm = ret.as_ref().unwrap().parse().unwrap();
+		assert!(solution.len() <= 21 && cube.apply(&solution).is_solved());
+	}
+	assert_eq!(results[21], Err(SolveError::InvalidFacelet));
+	assert!(solver.solve_batch::<&str>(&[], &BatchOptions::new()).is_empty());
+	let results = solver.solve_batch(&facelets, &BatchOptions::new().workers(3));
+	assert!(results.iter().take(21).all(Result::is_ok));
+	let cancel = CancelToken::new();
+	cancel.cancel();
+	let results = solver.solve_batch(&facelets[..20], &BatchOptions::new().workers(2).cancel(cancel));
+	assert!(results.iter().all(|ret| *ret == Err(SolveError::Cancelled)));
+}
+
+#[test]
+fn solver_stats() {
+	let solver = Solver::builder().build();
+	for _ in 0..5 {
+		let cube = Cube::random();
+		let (ret, stats) = solver.solve_with_stats(&cube);
+		let solution = ret.unwrap();
+		assert!(cube.apply(&solution).is_solved());
+		assert_eq!(solution.len(), stats.phase1_length + stats.phase2_length + stats.pre_moves, "stats={stats:?}");
+		assert!(stats.probes >= 1 && stats.phase1_nodes >= stats.probes && stats.phase2_nodes >= 1, "stats={stats:?}");
+		assert!(stats.urf_idx < 6 && stats.elapsed > Duration::ZERO);
+	}
+
+	let (ret, stats) = solver.solve_with_stats(&Cube::solved());
+	assert_eq!(ret.unwrap().len(), 0);
+	assert_eq!((stats.probes, stats.phase1_length, stats.phase2_length), (1, 0, 0));
+
+	let cube = Cube::random();
+	let (ret, stats) = Solver::builder().max_length(16).max_probes(10).build().solve_with_stats(&cube);
+	assert_eq!(ret, Err(SolveError::ProbeLimit));
+	assert_eq!(stats.probes, 10);
+	assert_eq!((stats.phase1_length, stats.phase2_length, stats.pre_moves), (0, 0, 0));
+
+	let (ret, stats) = Solver::builder().threads(3).max_length(20).build().solve_with_stats(&cube);
+	assert!(cube.apply(&ret.unwrap()).is_solved());
+	assert!(stats.probes >= 1 && stats.phase1_nodes >= 1);
+}
@@ -0,0 +1,95 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use min2phase::{Cube, Solver, TableProfile, Tables};
+
+/// Counts the bytes allocated, to check the peak memory of the profiles
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// The tests measuring the peak run one at a time
+static MEASURE: Mutex<()> = Mutex::new(());
+
+unsafe impl GlobalAlloc for CountingAlloc {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+		PEAK.fetch_max(allocated, Ordering::Relaxed);
+		System.alloc(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+		System.dealloc(ptr, layout)
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// The result of `f` and the peak of the bytes allocated while running it
+fn peak_of<T>(f: impl FnOnce() -> T) -> (T, usize) {
+	let _guard = MEASURE.lock().unwrap();
+	let base = ALLOCATED.load(Ordering::Relaxed);
+	PEAK.store(base, Ordering::Relaxed);
+	let ret = f();
+	(ret, PEAK.load(Ordering::Relaxed) - base)
+}
+
+#[test]
+fn low_memory_peak() {
+	let (tables, build_peak) = peak_of(|| Tables::build_with_profile(TableProfile::LowMemory, |_| ()));
+	assert!(build_peak < 256 << 10, "building the tables peaks at {build_peak} bytes");
+	let solver = Solver::builder().tables(Arc::new(tables)).build();
+	let cube = Cube::random_with(&mut min2phase::seeded_rng(3));
+	let (solution, solve_peak) = peak_of(|| solver.solve(&cube).unwrap());
+	assert!(solve_peak < 4 << 10, "solving peaks at {solve_peak} bytes");
+	assert!(cube.apply(&solution).is_solved());
+}
+
+#[test]
+fn low_memory_solve() {
+	let tables = Tables::build_with_profile(TableProfile::LowMemory, |_| ());
+	assert_eq!(tables.profile(), TableProfile::LowMemory);
+	let bytes = tables.to_bytes();
+	assert!(bytes.len() < Tables::build().to_bytes().len() / 2);
+
+	let loaded = Tables::from_bytes(&bytes).unwrap();
+	assert_eq!(loaded.profile(), TableProfile::LowMemory);
+	assert_eq!(loaded.to_bytes(), bytes);
+
+	let solver = Solver::builder().tables(Arc::new(loaded)).build();
+	let standard = Solver::builder().build();
+	let mut rng = min2phase::seeded_rng(11);
+	for _ in 0..3 {
+		let cube = Cube::random_with(&mut rng);
+		let solution = solver.solve(&cube).unwrap();
+		assert!(cube.apply(&solution).is_solved());
+		assert!(solution.len() <= 21);
+		// the pruning of the profiles differ, not the search itself
+		assert_eq!(solution, standard.solve(&cube).unwrap());
+	}
+	let cube = Cube::from_moves("R U R' U' D2 F").unwrap();
+	assert_eq!(solver.solve_optimal(&cube).unwrap().len(), 6);
+}
+
+#[test]
+fn twist_flip_solve() {
+	let tables = Tables::build_with_profile(TableProfile::TwistFlip, |_| ());
+	let bytes = tables.to_bytes();
+	assert!(bytes.len() > Tables::build().to_bytes().len());
+	let loaded = Tables::from_bytes(&bytes).unwrap();
+	assert_eq!(loaded.profile(), TableProfile::TwistFlip);
+	assert_eq!(loaded.to_bytes(), bytes);
+
+	let solver = Solver::builder().tables(Arc::new(loaded)).max_length(20).build();
+	let standard = Solver::builder().max_length(20).build();
+	let mut rng = min2phase::seeded_rng(13);
+	for _ in 0..10 {
+		let cube = Cube::random_with(&mut rng);
+		let solution = solver.solve(&cube).unwrap();
+		assert!(cube.apply(&solution).is_solved());
+		assert_eq!(solution, standard.solve(&cube).unwrap());
+	}
+}
@@ -0,0 +1,94 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use min2phase::{Cube, Progress, Solver, Tables};
+
+fn temp_path(name: &str) -> PathBuf {
+	std::env::temp_dir().join(format!("min2phase-{}-{}", std::process::id(), name))
+}
+
+fn to_bytes(tables: &Tables) -> Vec<u8> {
+	let mut buf = Vec::new();
+	tables.write_to(&mut buf).unwrap();
+	buf
+}
+
+#[test]
+fn tables_save_load() {
+	let tables = Tables::build();
+	let path = temp_path("roundtrip");
+	tables.save(&path).unwrap();
+	let loaded = Tables::load(&path).unwrap();
+	assert_eq!(to_bytes(&loaded), to_bytes(&tables));
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn tables_corrupt() {
+	let bytes = to_bytes(&Tables::build());
+	let path = temp_path("corrupt");
+
+	assert_eq!(Tables::load(&path).err().unwrap().kind(), ErrorKind::NotFound);
+	for (offset, len) in [(0, bytes.len()), (4, bytes.len()), (1000, bytes.len()), (0, bytes.len() - 1)] {
+		let mut corrupt = bytes[..len].to_vec();
+		corrupt[offset] ^= 1;
+		assert_eq!(Tables::read_from(&mut &corrupt[..]).err().unwrap().kind(), ErrorKind::InvalidData);
+	}
+
+	std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+	assert!(Tables::load(&path).is_err());
+	let tables = Tables::load_or_build(&path);
+	assert_eq!(to_bytes(&tables), bytes);
+	assert_eq!(to_bytes(&Tables::load(&path).unwrap()), bytes);
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn tables_from_bytes() {
+	let bytes = Tables::build().to_bytes();
+	assert_eq!(bytes, to_bytes(&Tables::read_from(&mut &bytes[..]).unwrap()));
+	let mut corrupt = bytes.clone();
+	corrupt[1000] ^= 1;
+	assert!(Tables::from_bytes(&corrupt).is_none());
+
+	// as without std, the solver is given its own tables and the cube comes from a seeded rng
+	let solver = Solver::builder().tables(Arc::new(Tables::from_bytes(&bytes).unwrap())).build();
+	let cube = Cube::random_with(&mut min2phase::seeded_rng(7));
+	assert!(cube.apply(&solver.solve(&cube).unwrap()).is_solved());
+}
+
+#[test]
+fn tables_install() {
+	let path = temp_path("install");
+	let installed = Tables::load_or_build(&path).install().is_ok();
+	assert!(!installed || Tables::build().install().is_err());
+	let cube = Cube::random();
+	assert!(cube.apply(&cube.solve(21).unwrap()).is_solved());
+	let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "embedded-tables")]
+#[test]
+fn tables_embedded() {
+	assert_eq!(to_bytes(&Tables::embedded()), to_bytes(&Tables::build()));
+}
+
+#[test]
+fn tables_progress() {
+	let mut events = Vec::new();
+	let tables = Tables::build_with_progress(|progress| events.push(progress));
+	assert_eq!(to_bytes(&tables), to_bytes(&Tables::build()));
+	for table in ["eperm_sym2raw", "move_tables", "slice_twst_prun", "mperm_cperm_prun"] {
+		assert!(events.contains(&Progress::Done { table }), "{table} missing in {events:?}");
+	}
+	let depths: Vec<u32> = events.iter().filter_map(|progress| match progress {
+		Progress::Depth { table: "slice_flip_prun", depth, .. } => Some(*depth),
+		_ => None,
+	}).collect();
+	assert_eq!(depths, (1..=depths.len() as u32).collect::<Vec<_>>());
+
+	min2phase::init();
+	assert!(Tables::is_warm());
+	assert!(min2phase::init_with_progress(|progress| panic!("unexpected {progress:?}")));
+}